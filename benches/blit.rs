@@ -0,0 +1,149 @@
+//! compares the bulk drawing paths against a per-cell baseline on a 200x60 screen.
+//! run with `cargo bench`
+
+use rusted_console::*;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const COLUMNS: u16 = 200;
+const ROWS: u16 = 60;
+const ITERATIONS: u32 = 200;
+
+/// the windows opened on top of each other for one frame
+fn windows() -> Vec<(i32, i32, i32, i32)> {
+    (0..24).map(|i| (i * 7 % 150, i * 3 % 40, 50, 20)).collect()
+}
+
+/// copies one cell at a time with index maths and bounds checks per cell
+fn naive_copy(
+    source: (&[CharInfo], u16),
+    target: (&mut [CharInfo], u16),
+    src: XywhRect,
+    dst: Coord,
+) {
+    for row in 0..src.3 as usize {
+        for column in 0..src.2 as usize {
+            let from: usize =
+                (src.0 as usize + column) + (src.1 as usize + row) * source.1 as usize;
+            let to: usize = (dst.0 as usize + column) + (dst.1 as usize + row) * target.1 as usize;
+            if let (Some(cell), Some(slot)) = (source.0.get(from), target.0.get_mut(to)) {
+                *slot = *cell;
+            }
+        }
+    }
+}
+
+/// opens and closes the windows by saving cells one at a time and writing every cell through a
+/// freshly allocated string
+fn naive_frame(console: &mut Console) {
+    let mut saved: Vec<(Rect, Vec<CharInfo>)> = vec![];
+    for (x, y, w, h) in windows() {
+        let rect: Rect = Rect::from_xywh(x as u16, y as u16, w as u16, h as u16)
+            .clip_to(&Rect(0, 0, COLUMNS, ROWS));
+        let mut buffer: Vec<CharInfo> = vec![CharInfo::default(); rect.size().area()];
+        naive_copy(
+            (&console.buffer, COLUMNS),
+            (&mut buffer, rect.width()),
+            rect.to_xywh(),
+            Coord(0, 0),
+        );
+        for coord in rect.coords() {
+            set_console_cursor_position(console, coord);
+            write_console(console, &' '.to_string());
+        }
+        saved.push((rect, buffer));
+    }
+    while let Some((rect, buffer)) = saved.pop() {
+        naive_copy(
+            (&buffer, rect.width()),
+            (&mut console.buffer, COLUMNS),
+            XywhRect(0, 0, rect.width(), rect.height()),
+            rect.position(),
+        );
+    }
+}
+
+/// opens and closes the windows through [Rusted]
+fn bulk_frame(rusted: &mut Rusted) {
+    let mut saved: Vec<Window> = vec![];
+    for rect in windows() {
+        let (window, _) = rusted.open_window(&WindowOptions::new(rect).colors(15, 1));
+        saved.push(window);
+    }
+    while let Some(window) = saved.pop() {
+        rusted.close_window(&window);
+    }
+}
+
+fn time(mut run: impl FnMut()) -> Duration {
+    let start: Instant = Instant::now();
+    for _ in 0..ITERATIONS {
+        run();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn report(name: &str, naive: Duration, bulk: Duration) {
+    let speedup: f64 = naive.as_secs_f64() / bulk.as_secs_f64().max(f64::EPSILON);
+    println!("{name:<28} naive {naive:>12?}  bulk {bulk:>12?}  {speedup:>6.1}x");
+}
+
+fn main() {
+    let screen: Rect = Rect(0, 0, COLUMNS, ROWS);
+    let cells: usize = screen.size().area();
+
+    // whole screen copies
+    let source: Vec<CharInfo> = vec![CharInfo('#', Attribute(7)); cells];
+    let mut console: Console = Console::new(COLUMNS, ROWS);
+    let naive: Duration = time(|| {
+        naive_copy(
+            (&source, COLUMNS),
+            (&mut console.buffer, COLUMNS),
+            screen.to_xywh(),
+            Coord(0, 0),
+        );
+        black_box(&console);
+    });
+    let bulk: Duration = time(|| {
+        write_console_output(
+            &mut console,
+            &source,
+            Coord(COLUMNS, ROWS),
+            Coord(0, 0),
+            screen,
+        );
+        black_box(&console);
+    });
+    report("copy 200x60", naive, bulk);
+
+    // scrolling a region inside one console
+    let naive: Duration = time(|| {
+        let copy: Vec<CharInfo> = console.buffer.clone();
+        naive_copy(
+            (&copy, COLUMNS),
+            (&mut console.buffer, COLUMNS),
+            XywhRect(0, 1, COLUMNS, ROWS - 1),
+            Coord(0, 0),
+        );
+        black_box(&console);
+    });
+    let bulk: Duration = time(|| {
+        copy_console_output(&mut console, Rect(0, 1, COLUMNS, ROWS), Coord(0, 0));
+        black_box(&console);
+    });
+    report("move region 200x59", naive, bulk);
+
+    // many windows opening and closing
+    let mut console: Console = Console::new(COLUMNS, ROWS);
+    let mut rusted: Rusted = Rusted::new();
+    rusted.resize(COLUMNS, ROWS, ResizeMode::Clear);
+    let naive: Duration = time(|| {
+        naive_frame(&mut console);
+        black_box(&console);
+    });
+    let bulk: Duration = time(|| {
+        bulk_frame(&mut rusted);
+        black_box(&rusted.console);
+    });
+    report("24 windows open and close", naive, bulk);
+}
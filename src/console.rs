@@ -1,13 +1,16 @@
 use std::fmt;
 
+use crate::console_error::ConsoleError;
 use crate::constants::*;
+use crate::geometry::{Coord, Rect, Size, XywhRect};
+use crate::scrollback::Scrollback;
 
 /// composite of background and foreground color bitmasks
-#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct Attribute(pub u16);
 
 /// character, attribute
-#[derive(Clone, Copy, Default, PartialEq)]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
 pub struct CharInfo(pub char, pub Attribute);
 
 impl fmt::Debug for CharInfo {
@@ -17,31 +20,45 @@ impl fmt::Debug for CharInfo {
     }
 }
 
-/// x, y
-#[derive(Debug, Clone, Copy, Default, PartialEq)]
-pub struct Coord(pub u16, pub u16);
-
-/// a rectangle described by the left, top, right, bottom
-#[derive(Debug, Clone, Copy, Default, PartialEq)]
-pub struct Rect(pub u16, pub u16, pub u16, pub u16);
-
-impl Rect {
-    pub fn from_xywh(x: u16, y: u16, w: u16, h: u16) -> Self {
-        Self(x, y, x + w, y + h)
-    }
-
-    pub fn to_xywh(&self) -> Self {
-        Self(self.0, self.1, self.2 - self.0, self.3 - self.1)
+impl Attribute {
+    /// gets the attribute with the foreground intensity cleared and a black background
+    pub fn darkened(self) -> Attribute {
+        Attribute(self.0 & (FOREGROUND_RED | FOREGROUND_GREEN | FOREGROUND_BLUE))
     }
+}
 
-    /// gets the width of the rectangle
-    pub fn width(&self) -> u16 {
-        self.2 - self.0
-    }
+/// how the cells of a source buffer are combined with the cells they are written over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlitMode {
+    /// replaces character and attribute
+    #[default]
+    Copy,
+    /// like Copy, but source cells with this character are skipped
+    TransparentChar(char),
+    /// like Copy, but source cells with this attribute are skipped
+    TransparentAttribute(Attribute),
+    /// replaces only the character, the attribute underneath is kept
+    CharOnly,
+    /// replaces only the attribute, the character underneath is kept
+    AttributeOnly,
+    /// keeps the character underneath and darkens its attribute, the source only selects the
+    /// region
+    Darken,
+}
 
-    /// gets the height of the rectangle
-    pub fn height(&self) -> u16 {
-        self.3 - self.1
+impl BlitMode {
+    /// gets the cell that results from writing over onto under
+    pub fn blend(self, under: CharInfo, over: CharInfo) -> CharInfo {
+        match self {
+            BlitMode::Copy => over,
+            BlitMode::TransparentChar(key) if over.0 == key => under,
+            BlitMode::TransparentChar(_) => over,
+            BlitMode::TransparentAttribute(key) if over.1 == key => under,
+            BlitMode::TransparentAttribute(_) => over,
+            BlitMode::CharOnly => CharInfo(over.0, under.1),
+            BlitMode::AttributeOnly => CharInfo(under.0, over.1),
+            BlitMode::Darken => CharInfo(under.0, under.1.darkened()),
+        }
     }
 }
 
@@ -51,20 +68,142 @@ pub struct Console {
     pub buffer: Vec<CharInfo>,
     pub cursor: Coord,
     pub attribute: Attribute,
+    /// the visible part of the buffer, may be smaller than size
+    pub viewport: Rect,
+    /// rows that scrolled off the top of the buffer
+    pub scrollback: Scrollback,
+    /// one flag per row, set when the text of the row soft-wraps onto the next row
+    pub wrapped: Vec<bool>,
+    /// the changed columns of every row as start, end. changes made directly to `buffer` must be
+    /// reported with [Console::mark_dirty]
+    #[doc(hidden)]
+    dirty: Vec<(u16, u16)>,
 }
 
 impl Console {
     pub fn new(columns: u16, rows: u16) -> Self {
-        Self {
+        Self::try_new(columns, rows).expect("console buffer too large")
+    }
+
+    pub fn try_new(columns: u16, rows: u16) -> Result<Self, ConsoleError> {
+        let count: usize = util_cell_count(Coord(columns, rows))?;
+        Ok(Self {
             size: Coord(columns, rows),
-            buffer: vec![CharInfo::default(); (columns * rows) as usize],
+            buffer: vec![CharInfo::default(); count],
             cursor: Coord(0, 0),
             attribute: Attribute(FOREGROUND_RED | FOREGROUND_GREEN | FOREGROUND_BLUE),
+            viewport: Rect(0, 0, columns, rows),
+            scrollback: Scrollback::default(),
+            wrapped: vec![false; rows as usize],
+            dirty: vec![(0, columns); rows as usize],
+        })
+    }
+
+    /// records that the cells of rect changed
+    pub fn mark_dirty(&mut self, rect: Rect) {
+        let rect: Rect = rect.clip_to(&Rect(0, 0, self.size.0, self.size.1));
+        if rect.is_empty() {
+            return;
+        }
+        for span in self.dirty[rect.1 as usize..rect.3 as usize].iter_mut() {
+            *span = if span.0 >= span.1 {
+                (rect.0, rect.2)
+            } else {
+                (span.0.min(rect.0), span.1.max(rect.2))
+            };
+        }
+    }
+
+    /// records that every cell changed
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty = vec![(0, self.size.0); self.size.1 as usize];
+    }
+
+    /// checks if any cell changed since the dirty rects were last taken
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.iter().any(|span| span.0 < span.1)
+    }
+
+    /// gets the changed regions, rows with the same changed columns are merged into one rect
+    pub fn dirty_rects(&self) -> Vec<Rect> {
+        let mut rects: Vec<Rect> = vec![];
+        for (row, &(start, end)) in self.dirty.iter().enumerate() {
+            if start >= end {
+                continue;
+            }
+            let row: u16 = row as u16;
+            match rects.last_mut() {
+                Some(last) if last.3 == row && last.0 == start && last.2 == end => last.3 = row + 1,
+                _ => rects.push(Rect(start, row, end, row + 1)),
+            }
+        }
+        rects
+    }
+
+    /// gets the changed regions and marks every cell as clean
+    pub fn take_dirty_rects(&mut self) -> Vec<Rect> {
+        let rects: Vec<Rect> = self.dirty_rects();
+        for span in self.dirty.iter_mut() {
+            *span = (0, 0);
+        }
+        rects
+    }
+
+    /// records that the count cells starting at index of the buffer changed
+    fn mark_run_dirty(&mut self, index: usize, count: usize) {
+        let width: usize = self.size.0 as usize;
+        if count == 0 || width == 0 {
+            return;
+        }
+        let (first_row, last_row) = (index / width, (index + count - 1) / width);
+        if first_row == last_row {
+            let column: u16 = (index % width) as u16;
+            self.mark_dirty(Rect(
+                column,
+                first_row as u16,
+                column + count as u16,
+                first_row as u16 + 1,
+            ));
+        } else {
+            self.mark_dirty(Rect(0, first_row as u16, self.size.0, last_row as u16 + 1));
         }
     }
 }
 
+/// number of cells of a buffer of the given size, failing when it cannot be allocated
+fn util_cell_count(size: Coord) -> Result<usize, ConsoleError> {
+    let count: usize = (size.0 as usize)
+        .checked_mul(size.1 as usize)
+        .ok_or(ConsoleError::Overflow("buffer cell count"))?;
+    match count.checked_mul(std::mem::size_of::<CharInfo>()) {
+        Some(bytes) if bytes <= isize::MAX as usize => Ok(count),
+        _ => Err(ConsoleError::Overflow("buffer size in bytes")),
+    }
+}
+
+fn util_check_position(console: &Console, position: Coord) -> Result<(), ConsoleError> {
+    if position.0 < console.size.0 && position.1 < console.size.1 {
+        Ok(())
+    } else {
+        Err(ConsoleError::OutOfBounds {
+            position,
+            size: console.size,
+        })
+    }
+}
+
+fn util_check_region(region: Rect, size: Coord) -> Result<(), ConsoleError> {
+    if !region.is_valid() {
+        return Err(ConsoleError::InvalidRect(region));
+    }
+    if region.2 > size.0 || region.3 > size.1 {
+        return Err(ConsoleError::RegionOutOfBounds { region, size });
+    }
+    Ok(())
+}
+
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod console {
     use crate::*;
     #[test]
@@ -74,11 +213,33 @@ mod console {
         assert_eq!(console.size.1, 25);
         assert_eq!(console.buffer.len(), 40 * 25);
     }
+
+    #[test]
+    fn constructs_large_console() {
+        let console: Console = Console::new(300, 300);
+        assert_eq!(console.buffer.len(), 300 * 300);
+    }
+}
+
+/// replaces the buffer with size.0 * size.1 empty cells
+pub fn try_set_console_buffer_size(console: &mut Console, size: Coord) -> Result<(), ConsoleError> {
+    util_cell_count(size)?;
+    set_console_buffer_size(console, size);
+    Ok(())
 }
 
+/// replaces the buffer with size.0 * size.1 empty cells, panics when the buffer cannot be allocated
 pub fn set_console_buffer_size(console: &mut Console, size: Coord) {
-    console.buffer = vec![CharInfo::default(); (size.0 * size.1) as usize];
+    let showed_everything: bool = console.viewport == Rect(0, 0, console.size.0, console.size.1);
+    console.buffer = vec![CharInfo::default(); (size.0 as usize) * (size.1 as usize)];
+    console.wrapped = vec![false; size.1 as usize];
     console.size = size;
+    console.mark_all_dirty();
+    console.viewport = if showed_everything {
+        Rect(0, 0, size.0, size.1)
+    } else {
+        util_clamp_viewport(console.viewport, size)
+    };
 }
 
 #[cfg(test)]
@@ -94,51 +255,508 @@ mod set_console_buffer_size {
         assert_eq!(console.size.1, 25);
         assert_eq!(console.buffer.len(), 40 * 25);
     }
+
+    #[test]
+    fn keeps_partial_viewport_inside_buffer() {
+        let mut console: Console = Console::new(200, 500);
+        set_console_window_info(&mut console, Rect(150, 480, 230, 505));
+
+        set_console_buffer_size(&mut console, Coord(100, 50));
+
+        assert_eq!(console.viewport, Rect(20, 25, 100, 50));
+    }
 }
 
-pub fn set_console_attribute(console: &mut Console, attribute: Attribute) {
+/// how the contents of the buffer are treated by [resize_console_buffer]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizeMode {
+    /// discard the contents, like [set_console_buffer_size]
+    Clear,
+    /// keep the top-left contents, cropping or padding with empty cells
+    Crop,
+    /// rewrap soft-wrapped lines to the new width, rows that no longer fit go to the scrollback
+    Reflow,
+}
+
+/// resizes the buffer, keeping its contents as described by the mode and adjusting the cursor so
+/// that it stays on the same logical position
+pub fn try_resize_console_buffer(
+    console: &mut Console,
+    size: Coord,
+    mode: ResizeMode,
+) -> Result<(), ConsoleError> {
+    util_cell_count(size)?;
+    resize_console_buffer(console, size, mode);
+    Ok(())
+}
+
+/// same as [try_resize_console_buffer], panics when the buffer cannot be allocated
+pub fn resize_console_buffer(console: &mut Console, size: Coord, mode: ResizeMode) {
+    let old_size: Coord = console.size;
+    let old_buffer: Vec<CharInfo> = std::mem::take(&mut console.buffer);
+    let old_wrapped: Vec<bool> = std::mem::take(&mut console.wrapped);
+    let old_cursor: Coord = console.cursor;
+    set_console_buffer_size(console, size);
+    if size.0 == 0 || size.1 == 0 {
+        console.cursor = Coord(0, 0);
+        return;
+    }
+    match mode {
+        ResizeMode::Clear => {
+            console.cursor = Coord(0, 0);
+        }
+        ResizeMode::Crop => {
+            let (copy_width, copy_height) = (old_size.0.min(size.0), old_size.1.min(size.1));
+            util_copy_buffer::<CharInfo>(
+                (&old_buffer[..], old_size.0, old_size.1),
+                (&mut console.buffer[..], size.0, size.1),
+                XywhRect(0, 0, copy_width, copy_height),
+                Coord(0, 0),
+            );
+            if old_size.0 == size.0 {
+                for (flag, &wrapped) in console.wrapped.iter_mut().zip(old_wrapped.iter()) {
+                    *flag = wrapped;
+                }
+            }
+            console.cursor = Coord(old_cursor.0.min(size.0 - 1), old_cursor.1.min(size.1 - 1));
+        }
+        ResizeMode::Reflow => {
+            util_reflow(console, &old_buffer, &old_wrapped, old_size, old_cursor);
+        }
+    }
+}
+
+fn util_reflow(
+    console: &mut Console,
+    old_buffer: &[CharInfo],
+    old_wrapped: &[bool],
+    old_size: Coord,
+    old_cursor: Coord,
+) {
+    let Coord(width, height) = console.size;
+    let (width, height) = (width as usize, height as usize);
+
+    // join soft-wrapped rows into logical lines, remembering where the cursor is in them
+    let mut lines: Vec<Vec<CharInfo>> = vec![];
+    let mut cursor: (usize, usize) = (0, 0);
+    let mut line: Vec<CharInfo> = vec![];
+    for (row, cells) in old_buffer.chunks(old_size.0.max(1) as usize).enumerate() {
+        if row == old_cursor.1 as usize {
+            cursor = (lines.len(), line.len() + old_cursor.0 as usize);
+        }
+        line.extend_from_slice(cells);
+        if !old_wrapped.get(row).copied().unwrap_or(false) {
+            while line.last() == Some(&CharInfo::default()) {
+                line.pop();
+            }
+            lines.push(std::mem::take(&mut line));
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    // wrap the logical lines to the new width
+    let mut rows: Vec<(Vec<CharInfo>, bool)> = vec![];
+    let mut cursor_position: Coord = Coord(0, 0);
+    for (index, line) in lines.iter().enumerate() {
+        if index == cursor.0 {
+            let row: usize = rows.len() + cursor.1 / width;
+            cursor_position = Coord((cursor.1 % width) as u16, row as u16);
+        }
+        if line.is_empty() {
+            rows.push((vec![], false));
+            continue;
+        }
+        let count: usize = line.len().div_ceil(width);
+        for (part, cells) in line.chunks(width).enumerate() {
+            rows.push((cells.to_vec(), part + 1 < count));
+        }
+    }
+    let cursor_row: usize = cursor_position.1 as usize;
+    while rows.len() < cursor_row + 1 {
+        rows.push((vec![], false));
+    }
+
+    // drop empty rows below the cursor first, then move rows off the top into the scrollback
+    while rows.len() > height && rows.len() > cursor_row + 1 && rows[rows.len() - 1].0.is_empty() {
+        rows.pop();
+    }
+    let excess: usize = rows.len().saturating_sub(height);
+    for (cells, _) in rows.drain(0..excess) {
+        let mut cells: Vec<CharInfo> = cells;
+        cells.resize(width, CharInfo::default());
+        console.scrollback.push(cells);
+    }
+
+    for (row, (cells, wrapped)) in rows.into_iter().take(height).enumerate() {
+        let start: usize = row * width;
+        console.buffer[start..start + cells.len()].copy_from_slice(&cells);
+        console.wrapped[row] = wrapped;
+    }
+    console.cursor = Coord(
+        cursor_position.0,
+        cursor_row.saturating_sub(excess).min(height - 1) as u16,
+    );
+}
+
+#[cfg(test)]
+mod resize_console_buffer {
+    use crate::*;
+
+    fn row_text(console: &Console, row: usize) -> String {
+        let width: usize = console.size.0 as usize;
+        console.buffer[row * width..(row + 1) * width]
+            .iter()
+            .map(|cell| if cell.0 == '\0' { ' ' } else { cell.0 })
+            .collect()
+    }
+
+    #[test]
+    fn crops_and_pads_top_left_contents() {
+        let mut console: Console = Console::new(4, 2);
+        write_console(&mut console, "abcdefg");
+
+        resize_console_buffer(&mut console, Coord(3, 3), ResizeMode::Crop);
+
+        assert_eq!(row_text(&console, 0), "abc");
+        assert_eq!(row_text(&console, 1), "efg");
+        assert_eq!(row_text(&console, 2), "   ");
+        assert_eq!(console.cursor, Coord(2, 1));
+    }
+
+    #[test]
+    fn reflows_soft_wrapped_lines() {
+        let mut console: Console = Console::new(4, 3);
+        write_console(&mut console, "abcdef\nxy");
+        assert_eq!(console.wrapped, vec![true, false, false]);
+
+        resize_console_buffer(&mut console, Coord(8, 3), ResizeMode::Reflow);
+        assert_eq!(row_text(&console, 0), "abcdef  ");
+        assert_eq!(row_text(&console, 1), "xy      ");
+        assert_eq!(console.cursor, Coord(2, 1));
+
+        resize_console_buffer(&mut console, Coord(2, 3), ResizeMode::Reflow);
+        assert_eq!(console.scrollback.len(), 2);
+        assert_eq!(row_text(&console, 0), "ef");
+        assert_eq!(row_text(&console, 1), "xy");
+        assert_eq!(console.cursor, Coord(0, 2));
+    }
+}
+
+/// moves and resizes the viewport, the window must lie inside the buffer
+pub fn try_set_console_window_info(
+    console: &mut Console,
+    window: Rect,
+) -> Result<(), ConsoleError> {
+    util_check_region(window, console.size)?;
+    if console.viewport != window {
+        console.viewport = window;
+        console.mark_all_dirty();
+    }
+    Ok(())
+}
+
+/// moves and resizes the viewport, it is shrunk and shifted as needed to stay inside the buffer
+pub fn set_console_window_info(console: &mut Console, window: Rect) {
+    let _ = try_set_console_window_info(console, util_clamp_viewport(window, console.size));
+}
+
+/// pans the viewport by dx columns and dy rows, the moved viewport must lie inside the buffer
+pub fn try_scroll_console_window(
+    console: &mut Console,
+    dx: i32,
+    dy: i32,
+) -> Result<(), ConsoleError> {
+    let Rect(left, top, right, bottom) = console.viewport;
+    let moved: (i32, i32, i32, i32) = (
+        left as i32 + dx,
+        top as i32 + dy,
+        right as i32 + dx,
+        bottom as i32 + dy,
+    );
+    let inside: bool = moved.0 >= 0
+        && moved.1 >= 0
+        && moved.2 <= console.size.0 as i32
+        && moved.3 <= console.size.1 as i32;
+    if !inside {
+        let clamp = |value: i32| value.clamp(0, u16::MAX as i32) as u16;
+        return Err(ConsoleError::RegionOutOfBounds {
+            region: Rect(
+                clamp(moved.0),
+                clamp(moved.1),
+                clamp(moved.2),
+                clamp(moved.3),
+            ),
+            size: console.size,
+        });
+    }
+    console.viewport = Rect(
+        moved.0 as u16,
+        moved.1 as u16,
+        moved.2 as u16,
+        moved.3 as u16,
+    );
+    if dx != 0 || dy != 0 {
+        console.mark_all_dirty();
+    }
+    Ok(())
+}
+
+/// pans the viewport by dx columns and dy rows, stopping at the edges of the buffer
+pub fn scroll_console_window(console: &mut Console, dx: i32, dy: i32) {
+    let Rect(left, top, right, bottom) = console.viewport;
+    let max_dx: i32 = console.size.0 as i32 - right as i32;
+    let max_dy: i32 = console.size.1 as i32 - bottom as i32;
+    let dx: i32 = dx.clamp(-(left as i32), max_dx.max(-(left as i32)));
+    let dy: i32 = dy.clamp(-(top as i32), max_dy.max(-(top as i32)));
+    let _ = try_scroll_console_window(console, dx, dy);
+}
+
+/// pans the viewport by the smallest amount that brings the cursor into view, the cursor must be
+/// inside the buffer
+pub fn try_ensure_console_cursor_visible(console: &mut Console) -> Result<(), ConsoleError> {
+    util_check_position(console, console.cursor)?;
+    ensure_console_cursor_visible(console);
+    Ok(())
+}
+
+/// pans the viewport by the smallest amount that brings the cursor into view, stopping at the
+/// edges of the buffer when the cursor is outside of it
+pub fn ensure_console_cursor_visible(console: &mut Console) {
+    let Rect(left, top, right, bottom) = console.viewport;
+    let Coord(x, y) = console.cursor;
+    let dx: i32 = if x < left {
+        x as i32 - left as i32
+    } else if x >= right {
+        x as i32 - right as i32 + 1
+    } else {
+        0
+    };
+    let dy: i32 = if y < top {
+        y as i32 - top as i32
+    } else if y >= bottom {
+        y as i32 - bottom as i32 + 1
+    } else {
+        0
+    };
+    scroll_console_window(console, dx, dy);
+}
+
+#[cfg(test)]
+mod ensure_console_cursor_visible {
+    use crate::*;
+    #[test]
+    fn pans_viewport_to_cursor() {
+        let mut console: Console = Console::new(200, 500);
+        set_console_window_info(&mut console, Rect(0, 0, 80, 25));
+
+        set_console_cursor_position(&mut console, Coord(100, 30));
+        ensure_console_cursor_visible(&mut console);
+        assert_eq!(console.viewport, Rect(21, 6, 101, 31));
+
+        set_console_cursor_position(&mut console, Coord(5, 2));
+        ensure_console_cursor_visible(&mut console);
+        assert_eq!(console.viewport, Rect(5, 2, 85, 27));
+
+        scroll_console_window(&mut console, 1000, 1000);
+        assert_eq!(console.viewport, Rect(120, 475, 200, 500));
+    }
+}
+
+fn util_clamp_viewport(window: Rect, size: Coord) -> Rect {
+    let buffer: Rect = Rect(0, 0, size.0, size.1);
+    let Size(width, height) = window.size();
+    let (width, height) = (width.min(size.0), height.min(size.1));
+    let left: u16 = window.0.min(size.0 - width);
+    let top: u16 = window.1.min(size.1 - height);
+    Rect(left, top, left + width, top + height).clip_to(&buffer)
+}
+
+/// sets the attribute used by [write_console], every attribute is accepted
+pub fn try_set_console_attribute(
+    console: &mut Console,
+    attribute: Attribute,
+) -> Result<(), ConsoleError> {
     console.attribute = attribute;
+    Ok(())
 }
 
+pub fn set_console_attribute(console: &mut Console, attribute: Attribute) {
+    let _ = try_set_console_attribute(console, attribute);
+}
+
+/// index range of the run of count cells starting at start, the run must end inside the buffer
+fn util_run(
+    console: &Console,
+    count: u16,
+    start: Coord,
+) -> Result<std::ops::Range<usize>, ConsoleError> {
+    util_check_position(console, start)?;
+    let first: usize = (start.0 as usize) + (start.1 as usize) * (console.size.0 as usize);
+    if first + count as usize > console.buffer.len() {
+        return Err(ConsoleError::RunOutOfBounds {
+            start,
+            count,
+            size: console.size,
+        });
+    }
+    Ok(first..first + count as usize)
+}
+
+/// count of cells left in the buffer from start onwards, clamped to the given count
+fn util_clamp_run(console: &Console, count: u16, start: Coord) -> u16 {
+    let first: usize = (start.0 as usize) + (start.1 as usize) * (console.size.0 as usize);
+    let available: usize = console.buffer.len().saturating_sub(first);
+    count.min(available.min(u16::MAX as usize) as u16)
+}
+
+/// sets the attribute of count cells starting at start, wrapping from row to row
+pub fn try_fill_console_output_attribute(
+    console: &mut Console,
+    count: u16,
+    attribute: Attribute,
+    start: Coord,
+) -> Result<(), ConsoleError> {
+    let run = util_run(console, count, start)?;
+    console.mark_run_dirty(run.start, run.len());
+    for cell in console.buffer[run].iter_mut() {
+        cell.1 = attribute;
+    }
+    Ok(())
+}
+
+/// sets the attribute of count cells starting at start, stopping at the end of the buffer.
+/// nothing is filled when start is outside of the buffer
 pub fn fill_console_output_attribute(
     console: &mut Console,
     count: u16,
     attribute: Attribute,
     start: Coord,
 ) {
-    for n in 0..=count {
-        let index: usize =
-            (n as usize) + (start.0 as usize) + ((start.1 as usize) * (console.size.0 as usize));
-        if index >= console.buffer.len() {
-            break;
-        }
-        let cell: &mut CharInfo = &mut console.buffer[index];
-        cell.1 = attribute;
+    let count: u16 = util_clamp_run(console, count, start);
+    let _ = try_fill_console_output_attribute(console, count, attribute, start);
+}
+
+/// sets the character of count cells starting at start, wrapping from row to row
+pub fn try_fill_console_output_character(
+    console: &mut Console,
+    count: u16,
+    character: char,
+    start: Coord,
+) -> Result<(), ConsoleError> {
+    let run = util_run(console, count, start)?;
+    console.mark_run_dirty(run.start, run.len());
+    for cell in console.buffer[run].iter_mut() {
+        cell.0 = character;
     }
+    Ok(())
 }
 
+/// sets the character of count cells starting at start, stopping at the end of the buffer.
+/// nothing is filled when start is outside of the buffer
 pub fn fill_console_output_character(
     console: &mut Console,
     count: u16,
     character: char,
     start: Coord,
 ) {
-    for n in 0..=count {
-        let index: usize =
-            (n as usize) + (start.0 as usize) + ((start.1 as usize) * (console.size.0 as usize));
-        if index >= console.buffer.len() {
-            break;
-        }
-        let cell: &mut CharInfo = &mut console.buffer[index];
+    let count: u16 = util_clamp_run(console, count, start);
+    let _ = try_fill_console_output_character(console, count, character, start);
+}
+
+/// writes the characters of text into consecutive cells starting at start without changing their
+/// attributes or moving the cursor, wrapping from row to row
+pub fn try_write_console_output_character(
+    console: &mut Console,
+    text: &str,
+    start: Coord,
+) -> Result<(), ConsoleError> {
+    let count: usize = text.chars().count();
+    let run = util_run(console, count.min(u16::MAX as usize) as u16, start)?;
+    if count > run.len() {
+        return Err(ConsoleError::Overflow("character count"));
+    }
+    console.mark_run_dirty(run.start, run.len());
+    for (cell, character) in console.buffer[run].iter_mut().zip(text.chars()) {
         cell.0 = character;
     }
+    Ok(())
 }
 
-pub fn set_console_cursor_position(console: &mut Console, position: Coord) {
+/// same as [try_write_console_output_character], stopping at the end of the buffer. nothing is
+/// written when start is outside of the buffer
+pub fn write_console_output_character(console: &mut Console, text: &str, start: Coord) {
+    let count: usize = text.chars().count().min(u16::MAX as usize);
+    let count: usize = util_clamp_run(console, count as u16, start) as usize;
+    let end: usize = text
+        .char_indices()
+        .nth(count)
+        .map_or(text.len(), |(index, _)| index);
+    let _ = try_write_console_output_character(console, &text[..end], start);
+}
+
+#[cfg(test)]
+mod fill_console_output {
+    use crate::*;
+    #[test]
+    fn fills_exactly_count_cells() {
+        let mut console: Console = Console::new(4, 2);
+
+        fill_console_output_character(&mut console, 3, 'x', Coord(2, 0));
+        fill_console_output_attribute(&mut console, 100, Attribute(4), Coord(3, 1));
+
+        let text: String = console.buffer.iter().map(|cell| cell.0).collect();
+        assert_eq!(text, "\0\0xxx\0\0\0");
+        assert_eq!(console.buffer[7].1, Attribute(4));
+        assert_eq!(console.buffer[6].1, Attribute(0));
+    }
+
+    #[test]
+    fn reports_runs_past_the_end() {
+        let mut console: Console = Console::new(4, 2);
+
+        assert_eq!(
+            try_fill_console_output_character(&mut console, 3, 'x', Coord(2, 1)),
+            Err(ConsoleError::RunOutOfBounds {
+                start: Coord(2, 1),
+                count: 3,
+                size: Coord(4, 2)
+            })
+        );
+        assert_eq!(
+            try_fill_console_output_attribute(&mut console, 1, Attribute(1), Coord(4, 0)),
+            Err(ConsoleError::OutOfBounds {
+                position: Coord(4, 0),
+                size: Coord(4, 2)
+            })
+        );
+        assert_eq!(console.buffer, Console::new(4, 2).buffer);
+    }
+}
+
+/// moves the cursor, the position must be inside the buffer
+pub fn try_set_console_cursor_position(
+    console: &mut Console,
+    position: Coord,
+) -> Result<(), ConsoleError> {
+    util_check_position(console, position)?;
     console.cursor = position;
+    Ok(())
 }
 
-pub fn write_console(console: &mut Console, text: &str) {
+/// moves the cursor, positions outside of the buffer are kept as they are and [write_console]
+/// discards text written there
+pub fn set_console_cursor_position(console: &mut Console, position: Coord) {
+    if try_set_console_cursor_position(console, position).is_err() {
+        console.cursor = position;
+    }
+}
+
+/// writes text at the cursor with the current attribute, wrapping at the right edge and staying
+/// on the bottom row once it is reached. the cursor must be inside the buffer
+pub fn try_write_console(console: &mut Console, text: &str) -> Result<(), ConsoleError> {
+    util_check_position(console, console.cursor)?;
     for character in text.chars() {
         let index: usize =
             (console.cursor.0 as usize) + ((console.cursor.1 as usize) * (console.size.0 as usize));
@@ -148,6 +766,7 @@ pub fn write_console(console: &mut Console, text: &str) {
         let cell: &mut CharInfo = &mut console.buffer[index];
         match character {
             '\n' => {
+                util_set_wrapped(console, console.cursor.1, false);
                 console.cursor.0 = 0;
                 console.cursor.1 += 1;
                 console.cursor.1 = console.cursor.1.min(console.size.1 - 1);
@@ -155,8 +774,12 @@ pub fn write_console(console: &mut Console, text: &str) {
             ch => {
                 cell.0 = ch;
                 cell.1 = console.attribute;
+                console.mark_run_dirty(index, 1);
                 console.cursor.0 += 1;
                 if console.cursor.0 > console.size.0 - 1 {
+                    if console.cursor.1 + 1 < console.size.1 {
+                        util_set_wrapped(console, console.cursor.1, true);
+                    }
                     console.cursor.0 = 0;
                     console.cursor.1 += 1;
                     console.cursor.1 = console.cursor.1.min(console.size.1 - 1);
@@ -164,73 +787,444 @@ pub fn write_console(console: &mut Console, text: &str) {
             }
         }
     }
+    Ok(())
+}
+
+/// same as [try_write_console], nothing is written when the cursor is outside of the buffer
+pub fn write_console(console: &mut Console, text: &str) {
+    let _ = try_write_console(console, text);
+}
+
+fn util_set_wrapped(console: &mut Console, row: u16, wrapped: bool) {
+    if let Some(flag) = console.wrapped.get_mut(row as usize) {
+        *flag = wrapped;
+    }
+}
+
+/// scrolls the whole buffer up by lines rows, the rows leaving the top are kept in the scrollback
+/// and the rows entering at the bottom are blanked with the current attribute. lines must not
+/// exceed the number of rows
+pub fn try_scroll_console_screen_buffer(
+    console: &mut Console,
+    lines: u16,
+) -> Result<(), ConsoleError> {
+    if lines > console.size.1 {
+        return Err(ConsoleError::RegionOutOfBounds {
+            region: Rect(0, 0, console.size.0, lines),
+            size: console.size,
+        });
+    }
+    let width: usize = console.size.0 as usize;
+    let lines: usize = lines as usize;
+    for row in console.buffer.chunks(width.max(1)).take(lines) {
+        console.scrollback.push(row.to_vec());
+    }
+    console.buffer.drain(0..lines * width);
+    if lines > 0 {
+        console.mark_all_dirty();
+    }
+    let blank: CharInfo = CharInfo(' ', console.attribute);
+    console
+        .buffer
+        .resize(console.buffer.len() + lines * width, blank);
+    console.wrapped.drain(0..lines.min(console.wrapped.len()));
+    console.wrapped.resize(console.size.1 as usize, false);
+    Ok(())
+}
+
+/// same as [try_scroll_console_screen_buffer], scrolling by more lines than there are rows
+/// scrolls every row out
+pub fn scroll_console_screen_buffer(console: &mut Console, lines: u16) {
+    let _ = try_scroll_console_screen_buffer(console, lines.min(console.size.1));
+}
+
+#[cfg(test)]
+mod scroll_console_screen_buffer {
+    use crate::*;
+    #[test]
+    fn moves_evicted_rows_into_scrollback() {
+        let mut console: Console = Console::new(3, 2);
+        write_console(&mut console, "abcdef");
+
+        scroll_console_screen_buffer(&mut console, 1);
+
+        assert_eq!(console.scrollback.len(), 1);
+        assert_eq!(console.scrollback.line(0).unwrap()[2].0, 'c');
+        assert_eq!(console.buffer[0].0, 'd');
+        assert_eq!(console.buffer[3], CharInfo(' ', console.attribute));
+    }
+}
+
+/// checks that the src region lies inside the source buffer and that a copy of it placed at
+/// dst lies inside the target buffer
+fn util_check_copy(
+    source: (usize, Coord),
+    target: (usize, Coord),
+    src: Rect,
+    dst: Coord,
+) -> Result<(), ConsoleError> {
+    for (len, size) in [source, target] {
+        if len < (size.0 as usize) * (size.1 as usize) {
+            return Err(ConsoleError::BufferTooSmall { len, size });
+        }
+    }
+    util_check_region(src, source.1)?;
+    let pasted: Rect = Rect::try_from_xywh(dst.0, dst.1, src.width(), src.height())?;
+    util_check_region(pasted, target.1)
+}
+
+/// clips the src region and dst position so that the copy lies inside both buffers, an inverted
+/// src becomes an empty region
+fn util_clip_copy(source_size: Coord, target_size: Coord, src: Rect, dst: Coord) -> (Rect, Coord) {
+    let src: Rect = src.clip_to(&Rect(0, 0, source_size.0, source_size.1));
+    let target: Rect = Rect(0, 0, target_size.0, target_size.1);
+    let pasted: Rect = Rect::from_position_size(dst, src.size()).clip_to(&target);
+    (
+        Rect::from_position_size(src.position(), pasted.size()),
+        pasted.position(),
+    )
+}
+
+/// copies the src region of the console into buffer at dst, everything must be in bounds
+pub fn try_read_console_output(
+    console: &Console,
+    buffer: &mut [CharInfo],
+    buffer_size: Coord,
+    dst: Coord,
+    src: Rect,
+) -> Result<(), ConsoleError> {
+    util_check_copy(
+        (console.buffer.len(), console.size),
+        (buffer.len(), buffer_size),
+        src,
+        dst,
+    )?;
+    let source_buffer = (&console.buffer[..], console.size.0, console.size.1);
+    let target_buffer = (buffer, buffer_size.0, buffer_size.1);
+    util_copy_buffer::<CharInfo>(source_buffer, target_buffer, src.to_xywh(), dst);
+    Ok(())
 }
 
+/// copies the src region of the console into buffer at dst, only the part that lies inside both
+/// the console and the buffer is copied
 pub fn read_console_output(
     console: &Console,
-    buffer: &mut Vec<CharInfo>,
+    buffer: &mut [CharInfo],
     buffer_size: Coord,
     dst: Coord,
     src: Rect,
 ) {
-    // println!("read_console_output {:?} {:?}", src, dst);
-    let source_buffer = (&console.buffer, console.size.0, console.size.1);
-    let target_buffer = (buffer, buffer_size.0, buffer_size.1);
+    let (src, dst) = util_clip_copy(console.size, buffer_size, src, dst);
+    let _ = try_read_console_output(console, buffer, buffer_size, dst, src);
+}
+
+/// copies the src region of buffer into the console at dst, everything must be in bounds
+pub fn try_write_console_output(
+    console: &mut Console,
+    buffer: &[CharInfo],
+    buffer_size: Coord,
+    dst: Coord,
+    src: Rect,
+) -> Result<(), ConsoleError> {
+    util_check_copy(
+        (buffer.len(), buffer_size),
+        (console.buffer.len(), console.size),
+        src,
+        dst,
+    )?;
+    let source_buffer = (buffer, buffer_size.0, buffer_size.1);
+    let target_buffer = (&mut console.buffer[..], console.size.0, console.size.1);
     util_copy_buffer::<CharInfo>(source_buffer, target_buffer, src.to_xywh(), dst);
+    console.mark_dirty(Rect::from_position_size(dst, src.size()));
+    Ok(())
 }
 
+/// copies the src region of buffer into the console at dst, only the part that lies inside both
+/// the buffer and the console is copied
 pub fn write_console_output(
     console: &mut Console,
-    buffer: &Vec<CharInfo>,
+    buffer: &[CharInfo],
     buffer_size: Coord,
     dst: Coord,
     src: Rect,
 ) {
-    // println!("write_console_output {:?} {:?}", src, dst);
-    let source_buffer = (buffer, buffer_size.0, buffer_size.1);
-    let target_buffer = (&mut console.buffer, console.size.0, console.size.1);
-    util_copy_buffer::<CharInfo>(source_buffer, target_buffer, src.to_xywh(), dst);
+    let (src, dst) = util_clip_copy(buffer_size, console.size, src, dst);
+    let _ = try_write_console_output(console, buffer, buffer_size, dst, src);
 }
 
-fn util_copy_buffer<T: Clone + std::fmt::Debug>(
-    source_buffer: (&Vec<T>, u16, u16),
-    target_buffer: (&mut Vec<T>, u16, u16),
+/// combines the src region of buffer with the console cells at dst according to mode, everything
+/// must be in bounds
+pub fn try_write_console_output_with_mode(
+    console: &mut Console,
+    buffer: &[CharInfo],
+    buffer_size: Coord,
+    dst: Coord,
     src: Rect,
+    mode: BlitMode,
+) -> Result<(), ConsoleError> {
+    util_check_copy(
+        (buffer.len(), buffer_size),
+        (console.buffer.len(), console.size),
+        src,
+        dst,
+    )?;
+    let copy_width: usize = src.width() as usize;
+    for row in 0..src.height() as usize {
+        let copy_start: usize =
+            (src.0 as usize) + (src.1 as usize + row) * (buffer_size.0 as usize);
+        let paste_start: usize =
+            (dst.0 as usize) + (dst.1 as usize + row) * (console.size.0 as usize);
+        let cells: &[CharInfo] = &buffer[copy_start..copy_start + copy_width];
+        let slots: &mut [CharInfo] = &mut console.buffer[paste_start..paste_start + copy_width];
+        for (slot, cell) in slots.iter_mut().zip(cells) {
+            *slot = mode.blend(*slot, *cell);
+        }
+    }
+    console.mark_dirty(Rect::from_position_size(dst, src.size()));
+    Ok(())
+}
+
+/// combines the src region of buffer with the console cells at dst according to mode, only the
+/// part that lies inside both the buffer and the console is written
+pub fn write_console_output_with_mode(
+    console: &mut Console,
+    buffer: &[CharInfo],
+    buffer_size: Coord,
     dst: Coord,
+    src: Rect,
+    mode: BlitMode,
 ) {
-    // println!("util_copy_buffer({:?}, {:?})", src, dst);
+    let (src, dst) = util_clip_copy(buffer_size, console.size, src, dst);
+    let _ = try_write_console_output_with_mode(console, buffer, buffer_size, dst, src, mode);
+}
 
+/// moves the src region of the console to dst within the same buffer, the regions may overlap.
+/// everything must be in bounds
+pub fn try_copy_console_output(
+    console: &mut Console,
+    src: Rect,
+    dst: Coord,
+) -> Result<(), ConsoleError> {
+    util_check_copy(
+        (console.buffer.len(), console.size),
+        (console.buffer.len(), console.size),
+        src,
+        dst,
+    )?;
+    let width: usize = console.size.0 as usize;
+    let copy_width: usize = src.width() as usize;
+    let row_count: u16 = src.height();
+    // copy rows in the order that never reads a row that was already overwritten
+    let rows: Vec<u16> = if dst.1 > src.1 {
+        (0..row_count).rev().collect()
+    } else {
+        (0..row_count).collect()
+    };
+    for row in rows {
+        let copy_start: usize = (src.0 as usize) + ((src.1 + row) as usize) * width;
+        let paste_start: usize = (dst.0 as usize) + ((dst.1 + row) as usize) * width;
+        console
+            .buffer
+            .copy_within(copy_start..copy_start + copy_width, paste_start);
+    }
+    console.mark_dirty(Rect::from_position_size(dst, src.size()));
+    Ok(())
+}
+
+/// moves the src region of the console to dst within the same buffer, only the part that lies
+/// inside the console is copied
+pub fn copy_console_output(console: &mut Console, src: Rect, dst: Coord) {
+    let (src, dst) = util_clip_copy(console.size, console.size, src, dst);
+    let _ = try_copy_console_output(console, src, dst);
+}
+
+#[cfg(test)]
+mod write_console_output {
+    use crate::*;
+    #[test]
+    fn clips_partially_outside_regions() {
+        let mut console: Console = Console::new(4, 2);
+        let buffer: Vec<CharInfo> = vec![CharInfo('#', Attribute(1)); 9];
+
+        write_console_output(
+            &mut console,
+            &buffer,
+            Coord(3, 3),
+            Coord(2, 1),
+            Rect(0, 0, 3, 3),
+        );
+
+        let text: String = console.buffer.iter().map(|cell| cell.0).collect();
+        assert_eq!(text, "\0\0\0\0\0\0##");
+    }
+
+    #[test]
+    fn blends_cells_according_to_the_mode() {
+        let mut console: Console = Console::new(4, 1);
+        let under: Attribute = Attribute(FOREGROUND_INTENSITY | FOREGROUND_RED | BACKGROUND_BLUE);
+        write_console_output(
+            &mut console,
+            &[CharInfo('.', under); 4],
+            Coord(4, 1),
+            Coord(0, 0),
+            Rect(0, 0, 4, 1),
+        );
+        let sprite: Vec<CharInfo> = vec![
+            CharInfo('#', Attribute(2)),
+            CharInfo(' ', Attribute(2)),
+            CharInfo('#', Attribute(2)),
+            CharInfo(' ', Attribute(3)),
+        ];
+        let blit = |console: &mut Console, mode: BlitMode| {
+            write_console_output_with_mode(
+                console,
+                &sprite,
+                Coord(4, 1),
+                Coord(0, 0),
+                Rect(0, 0, 4, 1),
+                mode,
+            );
+            console.buffer.clone()
+        };
+
+        let cells: Vec<CharInfo> = blit(&mut console.clone(), BlitMode::TransparentChar(' '));
+        assert_eq!(cells[0], CharInfo('#', Attribute(2)));
+        assert_eq!(cells[1], CharInfo('.', under));
+
+        let cells: Vec<CharInfo> = blit(
+            &mut console.clone(),
+            BlitMode::TransparentAttribute(Attribute(2)),
+        );
+        assert_eq!(cells[0], CharInfo('.', under));
+        assert_eq!(cells[3], CharInfo(' ', Attribute(3)));
+
+        let cells: Vec<CharInfo> = blit(&mut console.clone(), BlitMode::CharOnly);
+        assert_eq!(cells[2], CharInfo('#', under));
+
+        let cells: Vec<CharInfo> = blit(&mut console.clone(), BlitMode::AttributeOnly);
+        assert_eq!(cells[3], CharInfo('.', Attribute(3)));
+
+        let cells: Vec<CharInfo> = blit(&mut console, BlitMode::Darken);
+        assert_eq!(cells[1], CharInfo('.', Attribute(FOREGROUND_RED)));
+    }
+
+    #[test]
+    fn copies_overlapping_regions_within_a_console() {
+        let mut console: Console = Console::new(4, 3);
+        write_console(&mut console, "abcdefghijkl");
+        console.take_dirty_rects();
+
+        copy_console_output(&mut console, Rect(0, 0, 3, 2), Coord(1, 1));
+
+        let text: String = console.buffer.iter().map(|cell| cell.0).collect();
+        assert_eq!(text, "abcdeabciefg");
+        assert_eq!(console.take_dirty_rects(), vec![Rect(1, 1, 4, 3)]);
+    }
+
+    #[test]
+    fn reports_invalid_copies() {
+        let mut console: Console = Console::new(4, 2);
+        let buffer: Vec<CharInfo> = vec![CharInfo::default(); 9];
+
+        assert_eq!(
+            try_write_console_output(
+                &mut console,
+                &buffer,
+                Coord(3, 3),
+                Coord(2, 1),
+                Rect(0, 0, 3, 3)
+            ),
+            Err(ConsoleError::RegionOutOfBounds {
+                region: Rect(2, 1, 5, 4),
+                size: Coord(4, 2)
+            })
+        );
+        assert_eq!(
+            try_write_console_output(
+                &mut console,
+                &buffer,
+                Coord(3, 3),
+                Coord(0, 0),
+                Rect(2, 2, 1, 1)
+            ),
+            Err(ConsoleError::InvalidRect(Rect(2, 2, 1, 1)))
+        );
+        assert_eq!(
+            try_write_console_output(
+                &mut console,
+                &buffer,
+                Coord(4, 4),
+                Coord(0, 0),
+                Rect(0, 0, 1, 1)
+            ),
+            Err(ConsoleError::BufferTooSmall {
+                len: 9,
+                size: Coord(4, 4)
+            })
+        );
+    }
+}
+
+/// copies the src region to dst, cells outside of either buffer are skipped
+fn util_copy_buffer<T: Copy>(
+    source_buffer: (&[T], u16, u16),
+    target_buffer: (&mut [T], u16, u16),
+    src: XywhRect,
+    dst: Coord,
+) {
     let (source_vec, source_width, source_height) = source_buffer;
-    let (target_vec, target_width, _target_height) = target_buffer;
-
-    let copy_width = src.2.min(source_width);
-    let copy_height = src.3.min(source_height);
-    let copy_left = src.0;
-    let copy_top = src.1;
-
-    // println!("source_width: {:?}\nsource_height: {:?}\ntarget_width: {:?}\ntarget_height: {:?}", source_width, source_height, target_width, target_height);
-    // println!("copy_width: {:?}\ncopy_height: {:?}\ncopy_left: {:?}\ncopy_top: {:?}", copy_width, copy_height, copy_left, copy_top);
-
-    for copy_row in copy_top..(copy_top + copy_height) {
-        let paste_row = dst.1 + (copy_row - copy_top);
-        for copy_col in copy_left..(copy_left + copy_width) {
-            let paste_col = dst.0 + (copy_col - copy_left);
-            let copy_index = (copy_col + (copy_row * source_width)) as usize;
-            if copy_index >= source_vec.len() {
-                // println!("@{:?},{:?} copy index out of bounds: {:?}/{:?}", copy_col, copy_row, copy_index, source_vec.len());
-                break;
-            }
-            let paste_index = (paste_col + (paste_row * target_width)) as usize;
-            if paste_index >= target_vec.len() {
-                // println!("@{:?},{:?} paste index out of bounds: {:?}/{:?}", paste_col, paste_row, paste_index, target_vec.len());
-                break;
-            }
-            target_vec[paste_index] = source_vec[copy_index].clone();
-            // println!(
-            //     "copy {:?} from {:?}, {:?} @{:?} to {:?}, {:?} @{:?}",
-            //     source_vec[copy_index].clone(),
-            //     copy_col, copy_row, copy_index, paste_col, paste_row, paste_index
-            // );
+    let (target_vec, target_width, target_height) = target_buffer;
+
+    // clip the copied region against both buffers
+    let copy_width: usize = (src.2 as usize)
+        .min((source_width as usize).saturating_sub(src.0 as usize))
+        .min((target_width as usize).saturating_sub(dst.0 as usize));
+    let copy_height: usize = (src.3 as usize)
+        .min((source_height as usize).saturating_sub(src.1 as usize))
+        .min((target_height as usize).saturating_sub(dst.1 as usize));
+
+    for row in 0..copy_height {
+        let copy_start: usize = (src.0 as usize) + (src.1 as usize + row) * (source_width as usize);
+        let paste_start: usize =
+            (dst.0 as usize) + (dst.1 as usize + row) * (target_width as usize);
+        match (
+            source_vec.get(copy_start..copy_start + copy_width),
+            target_vec.get_mut(paste_start..paste_start + copy_width),
+        ) {
+            (Some(cells), Some(slots)) => slots.copy_from_slice(cells),
+            _ => break,
         }
     }
 }
+
+#[cfg(test)]
+mod dirty_rects {
+    use crate::*;
+    #[test]
+    fn tracks_and_coalesces_changes() {
+        let mut console: Console = Console::new(10, 5);
+        assert_eq!(console.take_dirty_rects(), vec![Rect(0, 0, 10, 5)]);
+        assert!(!console.is_dirty());
+
+        let block: Vec<CharInfo> = vec![CharInfo('#', Attribute(7)); 6];
+        write_console_output(
+            &mut console,
+            &block,
+            Coord(3, 2),
+            Coord(2, 1),
+            Rect(0, 0, 3, 2),
+        );
+        set_console_cursor_position(&mut console, Coord(8, 4));
+        write_console(&mut console, "ab");
+        fill_console_output_attribute(&mut console, 12, Attribute(1), Coord(9, 3));
+
+        assert_eq!(
+            console.take_dirty_rects(),
+            vec![Rect(2, 1, 5, 3), Rect(0, 3, 10, 5)]
+        );
+        assert_eq!(console.dirty_rects(), vec![]);
+
+        scroll_console_screen_buffer(&mut console, 1);
+        assert_eq!(console.take_dirty_rects(), vec![Rect(0, 0, 10, 5)]);
+    }
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+
+use crate::geometry::{Coord, Rect};
+
+/// reasons for which the `try_` console functions refuse to do their work
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleError {
+    /// the position lies outside a buffer of the given size
+    OutOfBounds { position: Coord, size: Coord },
+    /// the region does not lie completely inside a buffer of the given size
+    RegionOutOfBounds { region: Rect, size: Coord },
+    /// the run of count cells starting at start extends past the end of the buffer
+    RunOutOfBounds {
+        start: Coord,
+        count: u16,
+        size: Coord,
+    },
+    /// the left edge lies right of the right edge or the top edge lies below the bottom edge
+    InvalidRect(Rect),
+    /// the buffer holds fewer cells than its size requires
+    BufferTooSmall { len: usize, size: Coord },
+    /// the named computation does not fit its integer type
+    Overflow(&'static str),
+}
+
+impl fmt::Display for ConsoleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsoleError::OutOfBounds { position, size } => write!(
+                f,
+                "position {},{} is outside of the {}x{} buffer",
+                position.0, position.1, size.0, size.1
+            ),
+            ConsoleError::RegionOutOfBounds { region, size } => write!(
+                f,
+                "region {},{}-{},{} is not inside of the {}x{} buffer",
+                region.0, region.1, region.2, region.3, size.0, size.1
+            ),
+            ConsoleError::RunOutOfBounds { start, count, size } => write!(
+                f,
+                "{} cells starting at {},{} run past the end of the {}x{} buffer",
+                count, start.0, start.1, size.0, size.1
+            ),
+            ConsoleError::InvalidRect(rect) => write!(
+                f,
+                "rectangle {},{}-{},{} is inverted",
+                rect.0, rect.1, rect.2, rect.3
+            ),
+            ConsoleError::BufferTooSmall { len, size } => write!(
+                f,
+                "buffer of {} cells is too small for {}x{}",
+                len, size.0, size.1
+            ),
+            ConsoleError::Overflow(what) => write!(f, "{} overflows", what),
+        }
+    }
+}
+
+impl std::error::Error for ConsoleError {}
@@ -15,3 +15,84 @@ pub const BOX_TOPRIGHT_DOUBLE: char = '\u{2557}';
 pub const BOX_BOTLEFT_DOUBLE: char = '\u{255A}';
 pub const BOX_BOTRIGHT_DOUBLE: char = '\u{255D}';
 pub const DOUBLE_RIGHT_ARROW: char = '\u{00BB}';
+pub const RIGHT_POINTER: char = '\u{25BA}';
+pub const UP_POINTER: char = '\u{25B2}';
+pub const DOWN_POINTER: char = '\u{25BC}';
+pub const CHECK_MARK: char = '\u{221A}';
+pub const ELLIPSIS: char = '\u{2026}';
+
+pub const BOX_HORIZONTAL_SINGLE: char = '\u{2500}';
+pub const BOX_VERTICAL_SINGLE: char = '\u{2502}';
+pub const BOX_TOPLEFT_SINGLE: char = '\u{250C}';
+pub const BOX_TOPRIGHT_SINGLE: char = '\u{2510}';
+pub const BOX_BOTLEFT_SINGLE: char = '\u{2514}';
+pub const BOX_BOTRIGHT_SINGLE: char = '\u{2518}';
+
+pub const BOX_HORIZONTAL_HEAVY: char = '\u{2501}';
+pub const BOX_VERTICAL_HEAVY: char = '\u{2503}';
+pub const BOX_TOPLEFT_HEAVY: char = '\u{250F}';
+pub const BOX_TOPRIGHT_HEAVY: char = '\u{2513}';
+pub const BOX_BOTLEFT_HEAVY: char = '\u{2517}';
+pub const BOX_BOTRIGHT_HEAVY: char = '\u{251B}';
+
+pub const BOX_TOPLEFT_ROUNDED: char = '\u{256D}';
+pub const BOX_TOPRIGHT_ROUNDED: char = '\u{256E}';
+pub const BOX_BOTLEFT_ROUNDED: char = '\u{2570}';
+pub const BOX_BOTRIGHT_ROUNDED: char = '\u{256F}';
+
+pub const BOX_LEFTTEE_SINGLE: char = '\u{251C}';
+pub const BOX_RIGHTTEE_SINGLE: char = '\u{2524}';
+
+pub const BLOCK_FULL: char = '\u{2588}';
+pub const BLOCK_LIGHT_SHADE: char = '\u{2591}';
+
+pub const RIGHT_ALT_PRESSED: u32 = 0x0001;
+pub const LEFT_ALT_PRESSED: u32 = 0x0002;
+pub const RIGHT_CTRL_PRESSED: u32 = 0x0004;
+pub const LEFT_CTRL_PRESSED: u32 = 0x0008;
+pub const SHIFT_PRESSED: u32 = 0x0010;
+
+pub const FROM_LEFT_1ST_BUTTON_PRESSED: u32 = 0x0001;
+pub const RIGHTMOST_BUTTON_PRESSED: u32 = 0x0002;
+
+pub const MOUSE_MOVED: u32 = 0x0001;
+pub const DOUBLE_CLICK: u32 = 0x0002;
+pub const MOUSE_WHEELED: u32 = 0x0004;
+
+pub const VK_BACK: u16 = 0x08;
+pub const VK_TAB: u16 = 0x09;
+pub const VK_RETURN: u16 = 0x0D;
+pub const VK_ESCAPE: u16 = 0x1B;
+pub const VK_SPACE: u16 = 0x20;
+pub const VK_PRIOR: u16 = 0x21;
+pub const VK_NEXT: u16 = 0x22;
+pub const VK_END: u16 = 0x23;
+pub const VK_HOME: u16 = 0x24;
+pub const VK_LEFT: u16 = 0x25;
+pub const VK_UP: u16 = 0x26;
+pub const VK_RIGHT: u16 = 0x27;
+pub const VK_DOWN: u16 = 0x28;
+pub const VK_INSERT: u16 = 0x2D;
+pub const VK_DELETE: u16 = 0x2E;
+pub const VK_0: u16 = 0x30;
+pub const VK_9: u16 = 0x39;
+pub const VK_A: u16 = 0x41;
+pub const VK_C: u16 = 0x43;
+pub const VK_F: u16 = 0x46;
+pub const VK_V: u16 = 0x56;
+pub const VK_X: u16 = 0x58;
+pub const VK_Y: u16 = 0x59;
+pub const VK_Z: u16 = 0x5A;
+pub const VK_APPS: u16 = 0x5D;
+pub const VK_F1: u16 = 0x70;
+pub const VK_F2: u16 = 0x71;
+pub const VK_F3: u16 = 0x72;
+pub const VK_F4: u16 = 0x73;
+pub const VK_F5: u16 = 0x74;
+pub const VK_F6: u16 = 0x75;
+pub const VK_F7: u16 = 0x76;
+pub const VK_F8: u16 = 0x77;
+pub const VK_F9: u16 = 0x78;
+pub const VK_F10: u16 = 0x79;
+pub const VK_F11: u16 = 0x7A;
+pub const VK_F12: u16 = 0x7B;
@@ -0,0 +1,154 @@
+use crate::input::InputEvent;
+use crate::rusted::Rusted;
+
+/// how a dialog was closed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogResult {
+    Ok,
+    Cancelled,
+    /// the index of the chosen option
+    Choice(usize),
+    /// the text that was entered
+    Text(String),
+}
+
+/// a window that takes all input while it is open
+pub trait Dialog {
+    /// draws the dialog, saving the cells underneath
+    fn open(&mut self, ctx: &mut Rusted);
+
+    /// restores the cells underneath the dialog
+    fn close(&mut self, ctx: &mut Rusted);
+
+    /// handles input while the dialog is on top, Some closes the dialog with the result
+    fn handle_event(&mut self, ctx: &mut Rusted, event: &InputEvent) -> Option<DialogResult>;
+}
+
+/// handle to a dialog pushed on a [ModalStack]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DialogId(usize);
+
+/// open dialogs, only the topmost one receives input. dialogs are closed in the reverse order
+/// they were opened, so the cells saved under each are restored correctly
+#[derive(Default)]
+pub struct ModalStack {
+    #[doc(hidden)]
+    dialogs: Vec<(DialogId, Box<dyn Dialog>)>,
+    #[doc(hidden)]
+    next_id: usize,
+}
+
+impl ModalStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// opens the dialog on top of the others
+    pub fn push(&mut self, ctx: &mut Rusted, mut dialog: Box<dyn Dialog>) -> DialogId {
+        let id: DialogId = DialogId(self.next_id);
+        self.next_id += 1;
+        dialog.open(ctx);
+        self.dialogs.push((id, dialog));
+        id
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dialogs.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.dialogs.len()
+    }
+
+    /// gets the dialog that receives input
+    pub fn top(&self) -> Option<DialogId> {
+        self.dialogs.last().map(|(id, _)| *id)
+    }
+
+    /// routes the event to the topmost dialog. when it finishes, it is closed and removed, and
+    /// its result is returned. None when there is no dialog or it is still open
+    pub fn handle_event(
+        &mut self,
+        ctx: &mut Rusted,
+        event: &InputEvent,
+    ) -> Option<(DialogId, DialogResult)> {
+        let (id, dialog) = self.dialogs.last_mut()?;
+        let id: DialogId = *id;
+        let result: DialogResult = dialog.handle_event(ctx, event)?;
+        self.pop(ctx);
+        Some((id, result))
+    }
+
+    /// closes and removes the topmost dialog
+    pub fn pop(&mut self, ctx: &mut Rusted) -> Option<DialogId> {
+        let (id, mut dialog) = self.dialogs.pop()?;
+        dialog.close(ctx);
+        Some(id)
+    }
+
+    /// closes every dialog, topmost first
+    pub fn clear(&mut self, ctx: &mut Rusted) {
+        while self.pop(ctx).is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod modal_stack {
+    use crate::test_util::key;
+    use crate::*;
+
+    #[test]
+    fn routes_input_to_the_top_dialog_and_returns_results() {
+        let mut rusted = Rusted::new();
+        rusted.outchars(0, 0, "background");
+        let before: Vec<CharInfo> = rusted.console.buffer.clone();
+        let mut stack: ModalStack = ModalStack::new();
+
+        let question: DialogId = stack.push(
+            &mut rusted,
+            Box::new(RustedChoice::new().with_choice(
+                "Save?",
+                vec![
+                    "  Yes".to_string(),
+                    "  No".to_string(),
+                    "  Cancel".to_string(),
+                ],
+            )),
+        );
+        let message: DialogId = stack.push(
+            &mut rusted,
+            Box::new(RustedMessage::new(true).with_lines(vec!["Hello"])),
+        );
+        assert_eq!(stack.top(), Some(message));
+
+        assert_eq!(stack.handle_event(&mut rusted, &key(VK_DOWN, 0)), None);
+        assert_eq!(
+            stack.handle_event(&mut rusted, &key(VK_RETURN, 0)),
+            Some((message, DialogResult::Ok))
+        );
+        stack.handle_event(&mut rusted, &key(VK_UP, 0));
+        assert_eq!(
+            stack.handle_event(&mut rusted, &key(VK_RETURN, 0)),
+            Some((question, DialogResult::Choice(2)))
+        );
+        assert!(stack.is_empty());
+        assert_eq!(rusted.console.buffer, before);
+    }
+
+    #[test]
+    fn reopens_hidden_dialogs() {
+        let mut rusted = Rusted::new();
+        let mut message: RustedMessage = RustedMessage::new(false);
+
+        message.show(&mut rusted, vec!["one"]);
+        message.hide(&mut rusted);
+        assert!(!message.is_open());
+        message.show(&mut rusted, vec!["two"]);
+        assert!(message.is_open());
+
+        let mut choice: RustedChoice = RustedChoice::new();
+        choice.show_yes_no(&mut rusted, "Quit?");
+        choice.move_cursor_prev();
+        assert_eq!(choice.selected_choice, Some(1));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::constants::*;
+use crate::input::InputEvent;
+use crate::window_manager::WindowId;
+
+/// handle to an element registered with a [FocusManager]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FocusId(usize);
+
+/// an element that can receive the focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Focusable {
+    /// x, y, w, h in screen coordinates, used for clicks and the focus indicator
+    pub rect: (i32, i32, i32, i32),
+    /// the window the element belongs to, None for elements outside of any window
+    pub window: Option<WindowId>,
+    /// elements are visited in ascending order, equal indices in the order they were added
+    pub tab_index: i32,
+    pub enabled: bool,
+}
+
+/// a change of focus, elements should redraw themselves when they receive one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusEvent {
+    Gained(FocusId),
+    Lost(FocusId),
+}
+
+/// tracks which element has the focus and moves it with Tab, Shift+Tab and clicks. only the
+/// elements of the active window take part
+#[derive(Debug, Clone, Default)]
+pub struct FocusManager {
+    #[doc(hidden)]
+    elements: Vec<Option<Focusable>>,
+    #[doc(hidden)]
+    focused: Option<FocusId>,
+    #[doc(hidden)]
+    active_window: Option<WindowId>,
+}
+
+impl FocusManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// registers an enabled element at the end of the tab order of its window
+    pub fn add(&mut self, rect: (i32, i32, i32, i32), window: Option<WindowId>) -> FocusId {
+        let tab_index: i32 = self
+            .elements
+            .iter()
+            .flatten()
+            .filter(|element| element.window == window)
+            .map(|element| element.tab_index + 1)
+            .max()
+            .unwrap_or(0);
+        self.elements.push(Some(Focusable {
+            rect,
+            window,
+            tab_index,
+            enabled: true,
+        }));
+        FocusId(self.elements.len() - 1)
+    }
+
+    /// unregisters the element, it loses the focus first
+    pub fn remove(&mut self, id: FocusId) -> Vec<FocusEvent> {
+        let events: Vec<FocusEvent> = if self.focused == Some(id) {
+            self.util_focus(None)
+        } else {
+            vec![]
+        };
+        if let Some(slot) = self.elements.get_mut(id.0) {
+            *slot = None;
+        }
+        events
+    }
+
+    pub fn element(&self, id: FocusId) -> Option<&Focusable> {
+        self.elements.get(id.0).and_then(|element| element.as_ref())
+    }
+
+    pub fn element_mut(&mut self, id: FocusId) -> Option<&mut Focusable> {
+        self.elements
+            .get_mut(id.0)
+            .and_then(|element| element.as_mut())
+    }
+
+    pub fn focused(&self) -> Option<FocusId> {
+        self.focused
+    }
+
+    /// gets the rect of the focused element, to draw the focus indicator with
+    /// [crate::Rusted::draw_focus_indicator]
+    pub fn focused_rect(&self) -> Option<(i32, i32, i32, i32)> {
+        self.focused
+            .and_then(|id| self.element(id))
+            .map(|element| element.rect)
+    }
+
+    pub fn active_window(&self) -> Option<WindowId> {
+        self.active_window
+    }
+
+    /// makes the elements of the window take part, the focus moves to its first element
+    pub fn set_active_window(&mut self, window: Option<WindowId>) -> Vec<FocusEvent> {
+        self.active_window = window;
+        let first: Option<FocusId> = self.util_order().first().copied();
+        self.util_focus(first)
+    }
+
+    /// moves the focus to the element, nothing happens when it cannot take the focus
+    pub fn focus(&mut self, id: FocusId) -> Vec<FocusEvent> {
+        if !self.util_order().contains(&id) {
+            return vec![];
+        }
+        self.util_focus(Some(id))
+    }
+
+    pub fn focus_next(&mut self) -> Vec<FocusEvent> {
+        self.util_step(1)
+    }
+
+    pub fn focus_prev(&mut self) -> Vec<FocusEvent> {
+        self.util_step(-1)
+    }
+
+    /// handles Tab, Shift+Tab and left clicks on elements. returns the focus changes, events that
+    /// did not change the focus return none
+    pub fn handle_event(&mut self, event: &InputEvent) -> Vec<FocusEvent> {
+        match event {
+            InputEvent::Key(key) if key.key_down && key.virtual_key_code == VK_TAB => {
+                if key.shift() {
+                    self.focus_prev()
+                } else {
+                    self.focus_next()
+                }
+            }
+            InputEvent::Mouse(mouse) if mouse.left_button() && mouse.event_flags == 0 => {
+                let (x, y) = (mouse.position.0 as i32, mouse.position.1 as i32);
+                let hit: Option<FocusId> = self.util_order().into_iter().find(|&id| {
+                    let (ex, ey, ew, eh) = self.element(id).unwrap().rect;
+                    x >= ex && x < ex + ew && y >= ey && y < ey + eh
+                });
+                match hit {
+                    Some(id) => self.focus(id),
+                    None => vec![],
+                }
+            }
+            _ => vec![],
+        }
+    }
+
+    /// gets the enabled elements of the active window in tab order
+    fn util_order(&self) -> Vec<FocusId> {
+        let mut order: Vec<(i32, usize)> = self
+            .elements
+            .iter()
+            .enumerate()
+            .filter_map(|(index, element)| element.map(|element| (index, element)))
+            .filter(|(_, element)| element.enabled && element.window == self.active_window)
+            .map(|(index, element)| (element.tab_index, index))
+            .collect();
+        order.sort();
+        order.into_iter().map(|(_, index)| FocusId(index)).collect()
+    }
+
+    fn util_step(&mut self, step: isize) -> Vec<FocusEvent> {
+        let order: Vec<FocusId> = self.util_order();
+        if order.is_empty() {
+            return vec![];
+        }
+        let count: isize = order.len() as isize;
+        let next: isize = match self
+            .focused
+            .and_then(|id| order.iter().position(|&o| o == id))
+        {
+            Some(index) => (index as isize + step).rem_euclid(count),
+            None if step > 0 => 0,
+            None => count - 1,
+        };
+        self.util_focus(Some(order[next as usize]))
+    }
+
+    fn util_focus(&mut self, id: Option<FocusId>) -> Vec<FocusEvent> {
+        if self.focused == id {
+            return vec![];
+        }
+        let mut events: Vec<FocusEvent> = vec![];
+        if let Some(old) = self.focused {
+            events.push(FocusEvent::Lost(old));
+        }
+        if let Some(new) = id {
+            events.push(FocusEvent::Gained(new));
+        }
+        self.focused = id;
+        events
+    }
+}
+
+#[cfg(test)]
+mod tab_order {
+    use crate::*;
+
+    fn tab(shift: bool) -> InputEvent {
+        let modifiers: u32 = if shift { SHIFT_PRESSED } else { 0 };
+        InputEvent::Key(KeyEvent::new(VK_TAB, '\t').with_modifiers(modifiers))
+    }
+
+    #[test]
+    fn traverses_enabled_elements_in_tab_order() {
+        let mut focus: FocusManager = FocusManager::new();
+        let name: FocusId = focus.add((0, 0, 10, 1), None);
+        let ok: FocusId = focus.add((0, 2, 4, 1), None);
+        let cancel: FocusId = focus.add((5, 2, 6, 1), None);
+        let hidden: FocusId = focus.add((0, 3, 4, 1), None);
+        focus.element_mut(ok).unwrap().tab_index = 10;
+        focus.element_mut(hidden).unwrap().enabled = false;
+
+        assert_eq!(
+            focus.handle_event(&tab(false)),
+            vec![FocusEvent::Gained(name)]
+        );
+        assert_eq!(
+            focus.handle_event(&tab(false)),
+            vec![FocusEvent::Lost(name), FocusEvent::Gained(cancel)]
+        );
+        focus.handle_event(&tab(false));
+        assert_eq!(focus.focused(), Some(ok));
+        focus.handle_event(&tab(false));
+        assert_eq!(focus.focused(), Some(name));
+        focus.handle_event(&tab(true));
+        assert_eq!(focus.focused(), Some(ok));
+
+        let click = InputEvent::Mouse(MouseEvent::new(
+            Coord(6, 2),
+            FROM_LEFT_1ST_BUTTON_PRESSED,
+            0,
+        ));
+        focus.handle_event(&click);
+        assert_eq!(focus.focused(), Some(cancel));
+        assert_eq!(focus.focused_rect(), Some((5, 2, 6, 1)));
+    }
+
+    #[test]
+    fn limits_traversal_to_the_active_window() {
+        let mut manager: WindowManager = WindowManager::new(20, 10);
+        let window: WindowId = manager.create((0, 0), Size(10, 5));
+        let mut focus: FocusManager = FocusManager::new();
+        let outside: FocusId = focus.add((0, 0, 1, 1), None);
+        let inside: FocusId = focus.add((1, 1, 1, 1), Some(window));
+
+        assert_eq!(
+            focus.set_active_window(Some(window)),
+            vec![FocusEvent::Gained(inside)]
+        );
+        focus.focus_next();
+        assert_eq!(focus.focused(), Some(inside));
+        assert_eq!(focus.focus(outside), vec![]);
+        assert_eq!(focus.remove(inside), vec![FocusEvent::Lost(inside)]);
+        assert_eq!(focus.focused(), None);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::console::{Attribute, CharInfo};
+use crate::constants::*;
+
+/// the cells of a frame as top, middle and bottom rows of left, center and right cells.
+/// the middle center cell fills the inside of the frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NinePatch(pub [[CharInfo; 3]; 3]);
+
+impl NinePatch {
+    /// builds a patch whose cells all use attribute
+    pub fn from_chars(chars: [[char; 3]; 3], attribute: Attribute) -> Self {
+        Self(chars.map(|row| row.map(|character| CharInfo(character, attribute))))
+    }
+
+    pub fn top_left(&self) -> CharInfo {
+        self.0[0][0]
+    }
+
+    pub fn top(&self) -> CharInfo {
+        self.0[0][1]
+    }
+
+    pub fn top_right(&self) -> CharInfo {
+        self.0[0][2]
+    }
+
+    pub fn left(&self) -> CharInfo {
+        self.0[1][0]
+    }
+
+    pub fn fill(&self) -> CharInfo {
+        self.0[1][1]
+    }
+
+    pub fn right(&self) -> CharInfo {
+        self.0[1][2]
+    }
+
+    pub fn bottom_left(&self) -> CharInfo {
+        self.0[2][0]
+    }
+
+    pub fn bottom(&self) -> CharInfo {
+        self.0[2][1]
+    }
+
+    pub fn bottom_right(&self) -> CharInfo {
+        self.0[2][2]
+    }
+}
+
+/// how the border of windows and buttons is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameStyle {
+    Single,
+    #[default]
+    Double,
+    Heavy,
+    /// single lines with rounded corners
+    Rounded,
+    /// `+`, `-` and `|`
+    Ascii,
+    /// full blocks
+    Block,
+    /// no border is drawn
+    None,
+    /// the cells of the patch are drawn as they are, ignoring the current colors
+    Custom(NinePatch),
+}
+
+impl FrameStyle {
+    /// gets the cells to draw the frame with, the built-in styles use attribute.
+    /// None when no frame is drawn
+    pub fn nine_patch(&self, attribute: Attribute) -> Option<NinePatch> {
+        let chars: [[char; 3]; 3] = match self {
+            FrameStyle::Single => [
+                [
+                    BOX_TOPLEFT_SINGLE,
+                    BOX_HORIZONTAL_SINGLE,
+                    BOX_TOPRIGHT_SINGLE,
+                ],
+                [BOX_VERTICAL_SINGLE, ' ', BOX_VERTICAL_SINGLE],
+                [
+                    BOX_BOTLEFT_SINGLE,
+                    BOX_HORIZONTAL_SINGLE,
+                    BOX_BOTRIGHT_SINGLE,
+                ],
+            ],
+            FrameStyle::Double => [
+                [
+                    BOX_TOPLEFT_DOUBLE,
+                    BOX_HORIZONTAL_DOUBLE,
+                    BOX_TOPRIGHT_DOUBLE,
+                ],
+                [BOX_VERTICAL_DOUBLE, ' ', BOX_VERTICAL_DOUBLE],
+                [
+                    BOX_BOTLEFT_DOUBLE,
+                    BOX_HORIZONTAL_DOUBLE,
+                    BOX_BOTRIGHT_DOUBLE,
+                ],
+            ],
+            FrameStyle::Heavy => [
+                [BOX_TOPLEFT_HEAVY, BOX_HORIZONTAL_HEAVY, BOX_TOPRIGHT_HEAVY],
+                [BOX_VERTICAL_HEAVY, ' ', BOX_VERTICAL_HEAVY],
+                [BOX_BOTLEFT_HEAVY, BOX_HORIZONTAL_HEAVY, BOX_BOTRIGHT_HEAVY],
+            ],
+            FrameStyle::Rounded => [
+                [
+                    BOX_TOPLEFT_ROUNDED,
+                    BOX_HORIZONTAL_SINGLE,
+                    BOX_TOPRIGHT_ROUNDED,
+                ],
+                [BOX_VERTICAL_SINGLE, ' ', BOX_VERTICAL_SINGLE],
+                [
+                    BOX_BOTLEFT_ROUNDED,
+                    BOX_HORIZONTAL_SINGLE,
+                    BOX_BOTRIGHT_ROUNDED,
+                ],
+            ],
+            FrameStyle::Ascii => [['+', '-', '+'], ['|', ' ', '|'], ['+', '-', '+']],
+            FrameStyle::Block => [
+                [BLOCK_FULL, BLOCK_FULL, BLOCK_FULL],
+                [BLOCK_FULL, ' ', BLOCK_FULL],
+                [BLOCK_FULL, BLOCK_FULL, BLOCK_FULL],
+            ],
+            FrameStyle::None => return None,
+            FrameStyle::Custom(patch) => return Some(*patch),
+        };
+        Some(NinePatch::from_chars(chars, attribute))
+    }
+}
+
+/// true is the default double frame, false is no frame
+impl From<bool> for FrameStyle {
+    fn from(with_frame: bool) -> Self {
+        if with_frame {
+            FrameStyle::Double
+        } else {
+            FrameStyle::None
+        }
+    }
+}
+
+impl From<NinePatch> for FrameStyle {
+    fn from(patch: NinePatch) -> Self {
+        FrameStyle::Custom(patch)
+    }
+}
@@ -0,0 +1,357 @@
+use std::ops::{Add, Sub};
+
+use crate::console_error::ConsoleError;
+
+/// x, y
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Coord(pub u16, pub u16);
+
+impl Coord {
+    /// moves the coordinate by dx, dy, saturating at the edges of the u16 range
+    pub fn translate(&self, dx: i32, dy: i32) -> Self {
+        Self(util_offset(self.0, dx), util_offset(self.1, dy))
+    }
+}
+
+/// adds the components, saturating at u16::MAX
+impl Add for Coord {
+    type Output = Coord;
+    fn add(self, other: Coord) -> Coord {
+        Coord(
+            self.0.saturating_add(other.0),
+            self.1.saturating_add(other.1),
+        )
+    }
+}
+
+/// subtracts the components, saturating at 0
+impl Sub for Coord {
+    type Output = Coord;
+    fn sub(self, other: Coord) -> Coord {
+        Coord(
+            self.0.saturating_sub(other.0),
+            self.1.saturating_sub(other.1),
+        )
+    }
+}
+
+/// width, height
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Size(pub u16, pub u16);
+
+impl Size {
+    /// gets the number of cells covered
+    pub fn area(&self) -> usize {
+        (self.0 as usize) * (self.1 as usize)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0 || self.1 == 0
+    }
+}
+
+/// the size of a buffer is stored as a [Coord] by the console api
+impl From<Coord> for Size {
+    fn from(coord: Coord) -> Self {
+        Self(coord.0, coord.1)
+    }
+}
+
+impl From<Size> for Coord {
+    fn from(size: Size) -> Self {
+        Self(size.0, size.1)
+    }
+}
+
+/// a rectangle described by the left, top, right, bottom
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rect(pub u16, pub u16, pub u16, pub u16);
+
+/// a rectangle described by the left, top, width, height
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct XywhRect(pub u16, pub u16, pub u16, pub u16);
+
+impl Rect {
+    /// the right and bottom edges saturate at u16::MAX
+    pub fn from_xywh(x: u16, y: u16, w: u16, h: u16) -> Self {
+        Self(x, y, x.saturating_add(w), y.saturating_add(h))
+    }
+
+    pub fn try_from_xywh(x: u16, y: u16, w: u16, h: u16) -> Result<Self, ConsoleError> {
+        let right: u16 = x
+            .checked_add(w)
+            .ok_or(ConsoleError::Overflow("rectangle right edge"))?;
+        let bottom: u16 = y
+            .checked_add(h)
+            .ok_or(ConsoleError::Overflow("rectangle bottom edge"))?;
+        Ok(Self(x, y, right, bottom))
+    }
+
+    /// the right and bottom edges saturate at u16::MAX
+    pub fn from_position_size(position: Coord, size: Size) -> Self {
+        Self::from_xywh(position.0, position.1, size.0, size.1)
+    }
+
+    /// converts to the left, top, width, height form
+    pub fn to_xywh(&self) -> XywhRect {
+        XywhRect(self.0, self.1, self.width(), self.height())
+    }
+
+    /// checks that the left edge is not right of the right edge and the top is not below the bottom
+    pub fn is_valid(&self) -> bool {
+        self.0 <= self.2 && self.1 <= self.3
+    }
+
+    /// checks if the rectangle covers no cells, inverted rectangles are empty
+    pub fn is_empty(&self) -> bool {
+        self.0 >= self.2 || self.1 >= self.3
+    }
+
+    /// gets the width of the rectangle, 0 for an inverted rectangle
+    pub fn width(&self) -> u16 {
+        self.2.saturating_sub(self.0)
+    }
+
+    /// gets the height of the rectangle, 0 for an inverted rectangle
+    pub fn height(&self) -> u16 {
+        self.3.saturating_sub(self.1)
+    }
+
+    /// gets the top-left corner
+    pub fn position(&self) -> Coord {
+        Coord(self.0, self.1)
+    }
+
+    pub fn size(&self) -> Size {
+        Size(self.width(), self.height())
+    }
+
+    /// checks if the cell at coord lies inside, the right and bottom edges are exclusive
+    pub fn contains(&self, coord: Coord) -> bool {
+        coord.0 >= self.0 && coord.0 < self.2 && coord.1 >= self.1 && coord.1 < self.3
+    }
+
+    /// checks if every cell of other lies inside, empty rectangles are contained everywhere
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.is_empty()
+            || (other.0 >= self.0 && other.1 >= self.1 && other.2 <= self.2 && other.3 <= self.3)
+    }
+
+    /// checks if the rectangles share at least one cell
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// gets the cells shared by both rectangles, None when they share none
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let clipped: Rect = self.clip_to(other);
+        if clipped.is_empty() {
+            None
+        } else {
+            Some(clipped)
+        }
+    }
+
+    /// gets the part of the rectangle inside bounds, an empty rectangle inside bounds when they
+    /// do not overlap
+    pub fn clip_to(&self, bounds: &Rect) -> Rect {
+        let left: u16 = self.0.clamp(bounds.0, bounds.2.max(bounds.0));
+        let top: u16 = self.1.clamp(bounds.1, bounds.3.max(bounds.1));
+        let right: u16 = self.2.min(bounds.2).max(left);
+        let bottom: u16 = self.3.min(bounds.3).max(top);
+        Rect(left, top, right, bottom)
+    }
+
+    /// gets the smallest rectangle covering both, empty rectangles are ignored
+    pub fn union(&self, other: &Rect) -> Rect {
+        if other.is_empty() {
+            return *self;
+        }
+        if self.is_empty() {
+            return *other;
+        }
+        Rect(
+            self.0.min(other.0),
+            self.1.min(other.1),
+            self.2.max(other.2),
+            self.3.max(other.3),
+        )
+    }
+
+    /// moves the rectangle by dx, dy, saturating at the edges of the u16 range
+    pub fn translate(&self, dx: i32, dy: i32) -> Rect {
+        Rect(
+            util_offset(self.0, dx),
+            util_offset(self.1, dy),
+            util_offset(self.2, dx),
+            util_offset(self.3, dy),
+        )
+    }
+
+    /// shrinks every edge by dx columns and dy rows, collapsing to an empty rectangle in the middle
+    pub fn inset(&self, dx: u16, dy: u16) -> Rect {
+        let (width, height) = (self.width(), self.height());
+        let dx: u16 = dx.min(width / 2);
+        let dy: u16 = dy.min(height / 2);
+        let left: u16 = self.0 + dx;
+        let top: u16 = self.1 + dy;
+        Rect(left, top, left + (width - 2 * dx), top + (height - 2 * dy))
+    }
+
+    /// grows every edge by dx columns and dy rows, saturating at the edges of the u16 range
+    pub fn outset(&self, dx: u16, dy: u16) -> Rect {
+        Rect(
+            self.0.saturating_sub(dx),
+            self.1.saturating_sub(dy),
+            self.2.saturating_add(dx),
+            self.3.saturating_add(dy),
+        )
+    }
+
+    /// splits into the columns left of offset and the rest, offset is clamped to the width
+    pub fn split_at_column(&self, offset: u16) -> (Rect, Rect) {
+        let column: u16 = self.0 + offset.min(self.width());
+        (
+            Rect(self.0, self.1, column, self.3),
+            Rect(column, self.1, self.2.max(column), self.3),
+        )
+    }
+
+    /// splits into the rows above offset and the rest, offset is clamped to the height
+    pub fn split_at_row(&self, offset: u16) -> (Rect, Rect) {
+        let row: u16 = self.1 + offset.min(self.height());
+        (
+            Rect(self.0, self.1, self.2, row),
+            Rect(self.0, row, self.2, self.3.max(row)),
+        )
+    }
+
+    /// gets a rectangle of this size centered within other, it is cropped to other when larger
+    pub fn center_within(&self, other: &Rect) -> Rect {
+        let width: u16 = self.width().min(other.width());
+        let height: u16 = self.height().min(other.height());
+        let left: u16 = other.0 + (other.width() - width) / 2;
+        let top: u16 = other.1 + (other.height() - height) / 2;
+        Rect(left, top, left + width, top + height)
+    }
+
+    /// iterates over the coordinates of the contained cells, row by row
+    pub fn coords(&self) -> RectCoords {
+        RectCoords {
+            rect: *self,
+            next: Coord(self.0, self.1),
+        }
+    }
+}
+
+impl From<XywhRect> for Rect {
+    fn from(rect: XywhRect) -> Self {
+        Rect::from_xywh(rect.0, rect.1, rect.2, rect.3)
+    }
+}
+
+impl From<Rect> for XywhRect {
+    fn from(rect: Rect) -> Self {
+        rect.to_xywh()
+    }
+}
+
+impl XywhRect {
+    /// converts to the left, top, right, bottom form
+    pub fn to_ltrb(&self) -> Rect {
+        Rect::from(*self)
+    }
+}
+
+/// iterates over the coordinates of the cells inside a [Rect]
+#[doc(hidden)]
+pub struct RectCoords {
+    rect: Rect,
+    next: Coord,
+}
+
+impl Iterator for RectCoords {
+    type Item = Coord;
+
+    fn next(&mut self) -> Option<Coord> {
+        if self.rect.is_empty() || self.next.1 >= self.rect.3 {
+            return None;
+        }
+        let current: Coord = self.next;
+        self.next.0 += 1;
+        if self.next.0 >= self.rect.2 {
+            self.next = Coord(self.rect.0, self.next.1 + 1);
+        }
+        Some(current)
+    }
+}
+
+fn util_offset(value: u16, delta: i32) -> u16 {
+    (value as i32 + delta).clamp(0, u16::MAX as i32) as u16
+}
+
+#[cfg(test)]
+mod rect {
+    use crate::*;
+    #[test]
+    fn handles_inverted_and_overflowing_rects() {
+        let inverted: Rect = Rect(10, 10, 5, 5);
+        assert!(!inverted.is_valid());
+        assert_eq!((inverted.width(), inverted.height()), (0, 0));
+
+        assert_eq!(
+            Rect::from_xywh(65000, 0, 1000, 1),
+            Rect(65000, 0, u16::MAX, 1)
+        );
+        assert_eq!(
+            Rect::try_from_xywh(65000, 0, 1000, 1),
+            Err(ConsoleError::Overflow("rectangle right edge"))
+        );
+    }
+
+    #[test]
+    fn intersects_and_unites() {
+        let a: Rect = Rect(0, 0, 10, 5);
+        let b: Rect = Rect(5, 2, 20, 8);
+        assert_eq!(a.intersection(&b), Some(Rect(5, 2, 10, 5)));
+        assert_eq!(a.intersection(&Rect(10, 0, 12, 5)), None);
+        assert_eq!(a.union(&b), Rect(0, 0, 20, 8));
+        assert_eq!(a.union(&Rect(3, 3, 3, 3)), a);
+        assert!(a.contains(Coord(9, 4)));
+        assert!(!a.contains(Coord(10, 4)));
+        assert!(a.contains_rect(&Rect(1, 1, 10, 5)));
+    }
+
+    #[test]
+    fn transforms_rects() {
+        let rect: Rect = Rect(2, 2, 12, 6);
+        assert_eq!(rect.translate(-5, 1), Rect(0, 3, 7, 7));
+        assert_eq!(rect.inset(1, 1), Rect(3, 3, 11, 5));
+        assert_eq!(rect.inset(10, 10), Rect(7, 4, 7, 4));
+        assert_eq!(rect.outset(3, 1), Rect(0, 1, 15, 7));
+        assert_eq!(
+            rect.split_at_column(4),
+            (Rect(2, 2, 6, 6), Rect(6, 2, 12, 6))
+        );
+        assert_eq!(rect.split_at_row(9), (rect, Rect(2, 6, 12, 6)));
+        assert_eq!(
+            Rect(0, 0, 4, 2).center_within(&Rect(0, 0, 80, 25)),
+            Rect(38, 11, 42, 13)
+        );
+    }
+
+    #[test]
+    fn converts_between_forms_and_iterates() {
+        let xywh: XywhRect = Rect(2, 3, 5, 7).to_xywh();
+        assert_eq!(xywh, XywhRect(2, 3, 3, 4));
+        assert_eq!(Rect::from(xywh), Rect(2, 3, 5, 7));
+        assert_eq!(Rect(2, 3, 5, 7).size(), Size(3, 4));
+
+        let coords: Vec<Coord> = Rect(1, 1, 3, 3).coords().collect();
+        assert_eq!(
+            coords,
+            vec![Coord(1, 1), Coord(2, 1), Coord(1, 2), Coord(2, 2)]
+        );
+        assert_eq!(Rect(1, 1, 1, 3).coords().count(), 0);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::constants::*;
+use crate::geometry::Coord;
+
+/// a key press or release, like KEY_EVENT_RECORD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub key_down: bool,
+    pub repeat_count: u16,
+    /// one of the VK_ constants, or the upper case ASCII code for letters and digits
+    pub virtual_key_code: u16,
+    /// the typed character, '\0' when the key does not type one
+    pub character: char,
+    /// a combination of the _PRESSED constants
+    pub control_key_state: u32,
+}
+
+impl KeyEvent {
+    /// a key press without modifiers
+    pub fn new(virtual_key_code: u16, character: char) -> Self {
+        Self {
+            key_down: true,
+            repeat_count: 1,
+            virtual_key_code,
+            character,
+            control_key_state: 0,
+        }
+    }
+
+    /// the press of a key that types character, letters and digits get their virtual key code
+    pub fn from_char(character: char) -> Self {
+        let code: u16 = if character.is_ascii_alphanumeric() {
+            character.to_ascii_uppercase() as u16
+        } else if character == ' ' {
+            VK_SPACE
+        } else {
+            0
+        };
+        Self::new(code, character)
+    }
+
+    pub fn with_modifiers(mut self, control_key_state: u32) -> Self {
+        self.control_key_state = control_key_state;
+        self
+    }
+
+    pub fn ctrl(&self) -> bool {
+        self.control_key_state & (LEFT_CTRL_PRESSED | RIGHT_CTRL_PRESSED) != 0
+    }
+
+    pub fn alt(&self) -> bool {
+        self.control_key_state & (LEFT_ALT_PRESSED | RIGHT_ALT_PRESSED) != 0
+    }
+
+    pub fn shift(&self) -> bool {
+        self.control_key_state & SHIFT_PRESSED != 0
+    }
+}
+
+/// a mouse move, press, release or wheel turn, like MOUSE_EVENT_RECORD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseEvent {
+    pub position: Coord,
+    /// the buttons held down after the event, a combination of the _BUTTON_PRESSED constants
+    pub button_state: u32,
+    pub control_key_state: u32,
+    /// 0 for a press or release, otherwise MOUSE_MOVED, DOUBLE_CLICK or MOUSE_WHEELED
+    pub event_flags: u32,
+}
+
+impl MouseEvent {
+    pub fn new(position: Coord, button_state: u32, event_flags: u32) -> Self {
+        Self {
+            position,
+            button_state,
+            control_key_state: 0,
+            event_flags,
+        }
+    }
+
+    pub fn left_button(&self) -> bool {
+        self.button_state & FROM_LEFT_1ST_BUTTON_PRESSED != 0
+    }
+
+    pub fn right_button(&self) -> bool {
+        self.button_state & RIGHTMOST_BUTTON_PRESSED != 0
+    }
+
+    pub fn moved(&self) -> bool {
+        self.event_flags & MOUSE_MOVED != 0
+    }
+
+    /// gets the wheel rotation kept in the high word of button_state, positive when rolled away
+    /// from the user
+    pub fn wheel_delta(&self) -> i16 {
+        if self.event_flags & MOUSE_WHEELED != 0 {
+            (self.button_state >> 16) as u16 as i16
+        } else {
+            0
+        }
+    }
+}
+
+/// one record of console input, like INPUT_RECORD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEvent {
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    /// the new size of the screen buffer
+    Resize(Coord),
+}
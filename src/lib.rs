@@ -1,13 +1,51 @@
 mod console;
+mod console_error;
 mod constants;
+mod dialog;
+mod focus;
+mod frame_style;
+mod geometry;
+mod input;
+mod line_canvas;
+mod list_box;
+mod menu;
 mod rusted;
 mod rusted_choice;
 mod rusted_message;
+mod scrollback;
+mod status_bar;
+mod surface;
+#[cfg(test)]
+mod test_util;
+mod text_area;
+mod text_input;
+mod theme;
 mod window;
+mod window_manager;
+mod window_mover;
+mod window_options;
 
 pub use crate::console::*;
+pub use crate::console_error::*;
 pub use crate::constants::*;
+pub use crate::dialog::*;
+pub use crate::focus::*;
+pub use crate::frame_style::*;
+pub use crate::geometry::*;
+pub use crate::input::*;
+pub use crate::line_canvas::*;
+pub use crate::list_box::*;
+pub use crate::menu::*;
 pub use crate::rusted::*;
 pub use crate::rusted_choice::*;
 pub use crate::rusted_message::*;
+pub use crate::scrollback::*;
+pub use crate::status_bar::*;
+pub use crate::surface::*;
+pub use crate::text_area::*;
+pub use crate::text_input::*;
+pub use crate::theme::*;
 pub use crate::window::Window;
+pub use crate::window_manager::*;
+pub use crate::window_mover::*;
+pub use crate::window_options::*;
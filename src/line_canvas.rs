@@ -0,0 +1,259 @@
+use crate::geometry::{Coord, Rect, Size};
+
+/// the weight of a box-drawing line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LineWeight {
+    Single,
+    Double,
+}
+
+/// the line segments that leave a cell towards each of its edges
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineArms {
+    pub up: Option<LineWeight>,
+    pub right: Option<LineWeight>,
+    pub down: Option<LineWeight>,
+    pub left: Option<LineWeight>,
+}
+
+use LineWeight::{Double as D, Single as S};
+
+/// box-drawing glyphs by their up, right, down and left arms
+const GLYPHS: [(char, [Option<LineWeight>; 4]); 44] = [
+    ('\u{2500}', [None, Some(S), None, Some(S)]),
+    ('\u{2502}', [Some(S), None, Some(S), None]),
+    ('\u{250C}', [None, Some(S), Some(S), None]),
+    ('\u{2510}', [None, None, Some(S), Some(S)]),
+    ('\u{2514}', [Some(S), Some(S), None, None]),
+    ('\u{2518}', [Some(S), None, None, Some(S)]),
+    ('\u{251C}', [Some(S), Some(S), Some(S), None]),
+    ('\u{2524}', [Some(S), None, Some(S), Some(S)]),
+    ('\u{252C}', [None, Some(S), Some(S), Some(S)]),
+    ('\u{2534}', [Some(S), Some(S), None, Some(S)]),
+    ('\u{253C}', [Some(S), Some(S), Some(S), Some(S)]),
+    ('\u{2574}', [None, None, None, Some(S)]),
+    ('\u{2575}', [Some(S), None, None, None]),
+    ('\u{2576}', [None, Some(S), None, None]),
+    ('\u{2577}', [None, None, Some(S), None]),
+    ('\u{2550}', [None, Some(D), None, Some(D)]),
+    ('\u{2551}', [Some(D), None, Some(D), None]),
+    ('\u{2554}', [None, Some(D), Some(D), None]),
+    ('\u{2557}', [None, None, Some(D), Some(D)]),
+    ('\u{255A}', [Some(D), Some(D), None, None]),
+    ('\u{255D}', [Some(D), None, None, Some(D)]),
+    ('\u{2560}', [Some(D), Some(D), Some(D), None]),
+    ('\u{2563}', [Some(D), None, Some(D), Some(D)]),
+    ('\u{2566}', [None, Some(D), Some(D), Some(D)]),
+    ('\u{2569}', [Some(D), Some(D), None, Some(D)]),
+    ('\u{256C}', [Some(D), Some(D), Some(D), Some(D)]),
+    ('\u{2552}', [None, Some(D), Some(S), None]),
+    ('\u{2553}', [None, Some(S), Some(D), None]),
+    ('\u{2555}', [None, None, Some(S), Some(D)]),
+    ('\u{2556}', [None, None, Some(D), Some(S)]),
+    ('\u{2558}', [Some(S), Some(D), None, None]),
+    ('\u{2559}', [Some(D), Some(S), None, None]),
+    ('\u{255B}', [Some(S), None, None, Some(D)]),
+    ('\u{255C}', [Some(D), None, None, Some(S)]),
+    ('\u{255E}', [Some(S), Some(D), Some(S), None]),
+    ('\u{255F}', [Some(D), Some(S), Some(D), None]),
+    ('\u{2561}', [Some(S), None, Some(S), Some(D)]),
+    ('\u{2562}', [Some(D), None, Some(D), Some(S)]),
+    ('\u{2564}', [None, Some(D), Some(S), Some(D)]),
+    ('\u{2565}', [None, Some(S), Some(D), Some(S)]),
+    ('\u{2567}', [Some(S), Some(D), None, Some(D)]),
+    ('\u{2568}', [Some(D), Some(S), None, Some(S)]),
+    ('\u{256A}', [Some(S), Some(D), Some(S), Some(D)]),
+    ('\u{256B}', [Some(D), Some(S), Some(D), Some(S)]),
+];
+
+impl LineArms {
+    pub fn is_empty(&self) -> bool {
+        self.to_array() == [None; 4]
+    }
+
+    /// combines the arms of both, keeping the heavier weight where both have an arm
+    pub fn merge(self, other: LineArms) -> LineArms {
+        let (a, b) = (self.to_array(), other.to_array());
+        Self::from_array([0, 1, 2, 3].map(|index| a[index].max(b[index])))
+    }
+
+    /// gets the arms of a box-drawing glyph, None for any other character
+    pub fn from_glyph(glyph: char) -> Option<LineArms> {
+        GLYPHS
+            .iter()
+            .find(|(character, _)| *character == glyph)
+            .map(|(_, arms)| Self::from_array(*arms))
+    }
+
+    /// gets the glyph that joins the arms, None when there are no arms.
+    /// there are no glyphs mixing weights along one axis, so the heavier weight is used for both
+    /// arms of an axis
+    pub fn glyph(&self) -> Option<char> {
+        if self.is_empty() {
+            return None;
+        }
+        let [up, right, down, left] = self.to_array();
+        let vertical: Option<LineWeight> = up.max(down);
+        let horizontal: Option<LineWeight> = left.max(right);
+        let mut arms: [Option<LineWeight>; 4] = [
+            up.and(vertical),
+            right.and(horizontal),
+            down.and(vertical),
+            left.and(horizontal),
+        ];
+        // a lone arm is the end of a line, which continues through the cell
+        if arms.iter().filter(|arm| arm.is_some()).count() == 1 {
+            for index in 0..4 {
+                if arms[index].is_some() {
+                    arms[(index + 2) % 4] = arms[index];
+                    break;
+                }
+            }
+        }
+        GLYPHS
+            .iter()
+            .find(|(_, glyph_arms)| *glyph_arms == arms)
+            .map(|(character, _)| *character)
+    }
+
+    fn to_array(self) -> [Option<LineWeight>; 4] {
+        [self.up, self.right, self.down, self.left]
+    }
+
+    fn from_array(arms: [Option<LineWeight>; 4]) -> Self {
+        let [up, right, down, left] = arms;
+        Self {
+            up,
+            right,
+            down,
+            left,
+        }
+    }
+}
+
+/// records the line segments that meet in each cell so that crossings resolve to the right
+/// junction glyphs
+#[derive(Debug, Clone)]
+pub struct LineCanvas {
+    #[doc(hidden)]
+    size: Size,
+    #[doc(hidden)]
+    cells: Vec<LineArms>,
+}
+
+impl LineCanvas {
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            cells: vec![LineArms::default(); size.area()],
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// gets the arms recorded at coord, empty outside of the canvas
+    pub fn arms(&self, coord: Coord) -> LineArms {
+        self.util_index(coord)
+            .map(|index| self.cells[index])
+            .unwrap_or_default()
+    }
+
+    /// merges arms into the cell at coord, the parts outside of the canvas are discarded
+    pub fn add(&mut self, x: i32, y: i32, arms: LineArms) {
+        if x < 0 || y < 0 || x > u16::MAX as i32 || y > u16::MAX as i32 {
+            return;
+        }
+        if let Some(index) = self.util_index(Coord(x as u16, y as u16)) {
+            self.cells[index] = self.cells[index].merge(arms);
+        }
+    }
+
+    /// records a horizontal line of length cells starting at x, y. a single cell gets both arms
+    pub fn hline(&mut self, x: i32, y: i32, length: i32, weight: LineWeight) {
+        for offset in 0..length.max(0) {
+            let arms: LineArms = LineArms {
+                left: (offset > 0 || length == 1).then_some(weight),
+                right: (offset < length - 1 || length == 1).then_some(weight),
+                ..LineArms::default()
+            };
+            self.add(x + offset, y, arms);
+        }
+    }
+
+    /// records a vertical line of length cells starting at x, y. a single cell gets both arms
+    pub fn vline(&mut self, x: i32, y: i32, length: i32, weight: LineWeight) {
+        for offset in 0..length.max(0) {
+            let arms: LineArms = LineArms {
+                up: (offset > 0 || length == 1).then_some(weight),
+                down: (offset < length - 1 || length == 1).then_some(weight),
+                ..LineArms::default()
+            };
+            self.add(x, y + offset, arms);
+        }
+    }
+
+    /// records the border of the w * h region at x, y
+    pub fn frame(&mut self, rect: (i32, i32, i32, i32), weight: LineWeight) {
+        let (x, y, w, h) = rect;
+        self.hline(x, y, w, weight);
+        self.hline(x, y + h - 1, w, weight);
+        self.vline(x, y, h, weight);
+        self.vline(x + w - 1, y, h, weight);
+    }
+
+    /// iterates over the cells that have arms together with their glyph
+    pub fn glyphs(&self) -> impl Iterator<Item = (Coord, char)> + '_ {
+        Rect::from_position_size(Coord(0, 0), self.size)
+            .coords()
+            .filter_map(|coord| self.arms(coord).glyph().map(|glyph| (coord, glyph)))
+    }
+
+    fn util_index(&self, coord: Coord) -> Option<usize> {
+        if coord.0 >= self.size.0 || coord.1 >= self.size.1 {
+            return None;
+        }
+        Some((coord.0 as usize) + (coord.1 as usize) * (self.size.0 as usize))
+    }
+}
+
+#[cfg(test)]
+mod line_arms {
+    use crate::*;
+    #[test]
+    fn resolves_junctions_across_weights() {
+        let mut canvas: LineCanvas = LineCanvas::new(Size(5, 5));
+        canvas.frame((0, 0, 5, 5), LineWeight::Double);
+        canvas.hline(0, 2, 5, LineWeight::Single);
+        canvas.vline(2, 0, 5, LineWeight::Double);
+        canvas.hline(3, 3, 1, LineWeight::Single);
+        canvas.vline(1, 3, 1, LineWeight::Double);
+
+        assert_eq!(canvas.arms(Coord(0, 0)).glyph(), Some('\u{2554}'));
+        assert_eq!(canvas.arms(Coord(2, 0)).glyph(), Some('\u{2566}'));
+        assert_eq!(canvas.arms(Coord(0, 2)).glyph(), Some('\u{255F}'));
+        assert_eq!(canvas.arms(Coord(2, 2)).glyph(), Some('\u{256B}'));
+        assert_eq!(canvas.arms(Coord(1, 1)).glyph(), None);
+        assert_eq!(canvas.arms(Coord(3, 3)).glyph(), Some('\u{2500}'));
+        assert_eq!(canvas.arms(Coord(1, 3)).glyph(), Some(BOX_VERTICAL_DOUBLE));
+        assert_eq!(canvas.glyphs().count(), 23);
+    }
+
+    #[test]
+    fn round_trips_glyphs_and_merges_arms() {
+        for glyph in
+            ('\u{2550}'..='\u{256C}').filter(|&glyph| LineArms::from_glyph(glyph).is_some())
+        {
+            assert_eq!(LineArms::from_glyph(glyph).unwrap().glyph(), Some(glyph));
+        }
+        let vertical: LineArms = LineArms::from_glyph(BOX_VERTICAL_DOUBLE).unwrap();
+        let horizontal: LineArms = LineArms::from_glyph('\u{2500}').unwrap();
+        assert_eq!(vertical.merge(horizontal).glyph(), Some('\u{256B}'));
+        let lone: LineArms = LineArms {
+            left: Some(LineWeight::Double),
+            ..LineArms::default()
+        };
+        assert_eq!(lone.glyph(), Some(BOX_HORIZONTAL_DOUBLE));
+    }
+}
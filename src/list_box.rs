@@ -0,0 +1,383 @@
+use crate::constants::*;
+use crate::input::{InputEvent, KeyEvent, MouseEvent};
+use crate::rusted::Rusted;
+
+/// supplies the items of a [ListBox], only the visible ones are asked for when drawing
+pub trait ListSource {
+    fn len(&self) -> usize;
+
+    fn item(&self, index: usize) -> String;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: AsRef<str>> ListSource for [T] {
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn item(&self, index: usize) -> String {
+        self[index].as_ref().to_string()
+    }
+}
+
+impl<T: AsRef<str>> ListSource for Vec<T> {
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn item(&self, index: usize) -> String {
+        self[index].as_ref().to_string()
+    }
+}
+
+/// what a [ListBox] did with an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListBoxEvent {
+    /// the selected item changed, or the list scrolled, and the list should be drawn again
+    Changed,
+    /// Enter was pressed or the item was double clicked
+    Activated(usize),
+}
+
+/// a scrolling list showing a window into the items of a [ListSource], with a scroll bar when
+/// the items do not fit, PgUp, PgDn, Home and End, mouse selection and type-ahead search
+#[derive(Debug, Clone)]
+pub struct ListBox {
+    /// x, y, w, h of the list relative to the drawing origin
+    pub rect: (i32, i32, i32, i32),
+    pub focused: bool,
+    #[doc(hidden)]
+    selected: usize,
+    /// the index of the first visible item
+    #[doc(hidden)]
+    scroll: usize,
+    /// the characters typed since the last other key
+    #[doc(hidden)]
+    search: String,
+}
+
+impl ListBox {
+    pub fn new(rect: (i32, i32, i32, i32)) -> Self {
+        Self {
+            rect,
+            focused: true,
+            selected: 0,
+            scroll: 0,
+            search: String::new(),
+        }
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    /// selects the item, clamped to the source, and scrolls it into view
+    pub fn select<L: ListSource + ?Sized>(&mut self, source: &L, index: usize) {
+        self.selected = index.min(source.len().saturating_sub(1));
+        self.util_scroll_to_selected(source);
+    }
+
+    /// the number of items shown at once
+    pub fn page_size(&self) -> usize {
+        self.rect.3.max(1) as usize
+    }
+
+    pub fn handle_event<L: ListSource + ?Sized>(
+        &mut self,
+        source: &L,
+        event: &InputEvent,
+    ) -> Option<ListBoxEvent> {
+        match event {
+            InputEvent::Key(key) if key.key_down => self.handle_key(source, key),
+            InputEvent::Mouse(mouse) => self.util_handle_mouse(source, mouse),
+            _ => None,
+        }
+    }
+
+    /// moves with Up, Down, PgUp, PgDn, Home and End, activates with Enter and selects the next
+    /// item starting with the typed characters
+    pub fn handle_key<L: ListSource + ?Sized>(
+        &mut self,
+        source: &L,
+        key: &KeyEvent,
+    ) -> Option<ListBoxEvent> {
+        let count: usize = source.len();
+        if count == 0 {
+            return None;
+        }
+        let page: usize = self.page_size();
+        let last: usize = count - 1;
+        let target: usize = match key.virtual_key_code {
+            VK_UP => self.selected.saturating_sub(1),
+            VK_DOWN => (self.selected + 1).min(last),
+            VK_PRIOR => self.selected.saturating_sub(page - 1),
+            VK_NEXT => (self.selected + page - 1).min(last),
+            VK_HOME => 0,
+            VK_END => last,
+            VK_RETURN => {
+                self.search.clear();
+                return Some(ListBoxEvent::Activated(self.selected));
+            }
+            VK_BACK if !self.search.is_empty() => {
+                self.search.pop();
+                return None;
+            }
+            _ if key.character > ' ' && !key.ctrl() && !key.alt() => {
+                return self.util_type_ahead(source, key.character);
+            }
+            _ => return None,
+        };
+        self.search.clear();
+        self.util_change(source, target)
+    }
+
+    /// draws the visible items with the colors of the theme, the selected one highlighted, and
+    /// a scroll bar in the last column when the items do not fit
+    pub fn draw<L: ListSource + ?Sized>(&self, ctx: &mut Rusted, source: &L) {
+        let (x, y, w, h) = self.rect;
+        if w <= 0 || h <= 0 {
+            return;
+        }
+        let count: usize = source.len();
+        let scroll_bar: bool = count > h as usize;
+        let width: usize = (w - scroll_bar as i32) as usize;
+        let normal: (u16, u16) = ctx.theme().input;
+        let selected: (u16, u16) = if self.focused {
+            ctx.theme().focused
+        } else {
+            ctx.theme().selected
+        };
+
+        ctx.push_state();
+        for row in 0..h {
+            let index: usize = self.scroll + row as usize;
+            let mut text: String = if index < count {
+                source.item(index).chars().take(width).collect()
+            } else {
+                String::new()
+            };
+            let shown: usize = text.chars().count();
+            text.push_str(&" ".repeat(width - shown));
+            let (fgc, bgc) = if index == self.selected && index < count {
+                selected
+            } else {
+                normal
+            };
+            ctx.set_fgcolor(fgc);
+            ctx.set_bgcolor(bgc);
+            ctx.outchars(x, y + row, &text);
+        }
+        if scroll_bar {
+            ctx.set_fgcolor(normal.0);
+            ctx.set_bgcolor(normal.1);
+            let (thumb_y, thumb_h) = util_thumb(h as usize, count, self.scroll);
+            for row in 0..h as usize {
+                let glyph: char = if row >= thumb_y && row < thumb_y + thumb_h {
+                    BLOCK_FULL
+                } else {
+                    BLOCK_LIGHT_SHADE
+                };
+                ctx.outchar(x + w - 1, y + row as i32, glyph);
+            }
+        }
+        ctx.pop_state();
+    }
+
+    /// selects the next item after the selected one starting with the typed characters, ignoring
+    /// case. typing the same character again steps through the items starting with it
+    fn util_type_ahead<L: ListSource + ?Sized>(
+        &mut self,
+        source: &L,
+        character: char,
+    ) -> Option<ListBoxEvent> {
+        let repeated: bool = self.search.chars().all(|c| c == character);
+        self.search.push(character);
+        let search: String = self.search.to_lowercase();
+        let (prefix, first): (String, usize) = if repeated {
+            (character.to_lowercase().collect(), self.selected + 1)
+        } else {
+            (search, self.selected)
+        };
+        let count: usize = source.len();
+        let found: Option<usize> = (0..count)
+            .map(|offset| (first + offset) % count)
+            .find(|&index| source.item(index).to_lowercase().starts_with(&prefix));
+        match found {
+            Some(index) => self.util_change(source, index),
+            None => None,
+        }
+    }
+
+    /// selects with a click, activates with a double click and scrolls with the wheel
+    fn util_handle_mouse<L: ListSource + ?Sized>(
+        &mut self,
+        source: &L,
+        mouse: &MouseEvent,
+    ) -> Option<ListBoxEvent> {
+        let (x, y, w, h) = self.rect;
+        let (mx, my) = (mouse.position.0 as i32, mouse.position.1 as i32);
+        if mx < x || mx >= x + w || my < y || my >= y + h {
+            return None;
+        }
+        let delta: i16 = mouse.wheel_delta();
+        if delta != 0 {
+            let top: usize = source.len().saturating_sub(self.page_size());
+            let scroll: usize = if delta > 0 {
+                self.scroll.saturating_sub(3)
+            } else {
+                (self.scroll + 3).min(top)
+            };
+            if scroll == self.scroll {
+                return None;
+            }
+            self.scroll = scroll;
+            return Some(ListBoxEvent::Changed);
+        }
+        let index: usize = self.scroll + (my - y) as usize;
+        if !mouse.left_button() || mouse.moved() || index >= source.len() {
+            return None;
+        }
+        self.search.clear();
+        if mouse.event_flags & DOUBLE_CLICK != 0 {
+            self.selected = index;
+            return Some(ListBoxEvent::Activated(index));
+        }
+        self.util_change(source, index)
+    }
+
+    fn util_change<L: ListSource + ?Sized>(
+        &mut self,
+        source: &L,
+        index: usize,
+    ) -> Option<ListBoxEvent> {
+        let (selected, scroll) = (self.selected, self.scroll);
+        self.select(source, index);
+        if (selected, scroll) == (self.selected, self.scroll) {
+            None
+        } else {
+            Some(ListBoxEvent::Changed)
+        }
+    }
+
+    fn util_scroll_to_selected<L: ListSource + ?Sized>(&mut self, source: &L) {
+        let page: usize = self.page_size();
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + page {
+            self.scroll = self.selected + 1 - page;
+        }
+        self.scroll = self.scroll.min(source.len().saturating_sub(page));
+    }
+}
+
+/// gets the first row and the length of the scroll bar thumb
+fn util_thumb(height: usize, count: usize, scroll: usize) -> (usize, usize) {
+    let length: usize = (height * height / count).clamp(1, height);
+    let top: usize = count - height;
+    let position: usize = ((height - length) * scroll.min(top))
+        .checked_div(top)
+        .unwrap_or(0);
+    (position, length)
+}
+
+#[cfg(test)]
+mod virtual_list {
+    use crate::test_util::{key, row};
+    use crate::*;
+
+    /// numbers the items without storing them
+    struct Numbers(usize);
+
+    impl ListSource for Numbers {
+        fn len(&self) -> usize {
+            self.0
+        }
+
+        fn item(&self, index: usize) -> String {
+            format!("item {}", index)
+        }
+    }
+
+    #[test]
+    fn pages_through_large_sources() {
+        let source: Numbers = Numbers(100_000);
+        let mut list: ListBox = ListBox::new((0, 0, 12, 5));
+
+        assert_eq!(
+            list.handle_event(&source, &key(VK_NEXT, 0)),
+            Some(ListBoxEvent::Changed)
+        );
+        assert_eq!((list.selected(), list.scroll()), (4, 0));
+        list.handle_event(&source, &key(VK_NEXT, 0));
+        assert_eq!((list.selected(), list.scroll()), (8, 4));
+        list.handle_event(&source, &key(VK_END, 0));
+        assert_eq!((list.selected(), list.scroll()), (99_999, 99_995));
+        assert_eq!(list.handle_event(&source, &key(VK_DOWN, 0)), None);
+        list.handle_event(&source, &key(VK_HOME, 0));
+        assert_eq!((list.selected(), list.scroll()), (0, 0));
+        assert_eq!(
+            list.handle_event(&source, &key(VK_RETURN, 0)),
+            Some(ListBoxEvent::Activated(0))
+        );
+    }
+
+    #[test]
+    fn draws_only_the_visible_items_with_a_scroll_bar() {
+        let mut rusted = Rusted::new();
+        let source: Numbers = Numbers(100_000);
+        let mut list: ListBox = ListBox::new((0, 0, 12, 3));
+        list.select(&source, 99_999);
+        list.draw(&mut rusted, &source);
+
+        assert_eq!(row(&rusted, 0, 12), "item 99997 \u{2591}");
+        assert_eq!(row(&rusted, 2, 12), "item 99999 \u{2588}");
+        let focused: Attribute = Rusted::color_attribute(0, 6);
+        assert_eq!(rusted.console.get_cell(Coord(0, 2)).unwrap().1, focused);
+        assert_ne!(rusted.console.get_cell(Coord(0, 1)).unwrap().1, focused);
+    }
+
+    #[test]
+    fn searches_by_typed_prefix() {
+        let fruits: Vec<&str> = vec!["Apple", "Banana", "Blueberry", "Cherry", "berry"];
+        let mut list: ListBox = ListBox::new((0, 0, 12, 3));
+        let typed = |list: &mut ListBox, character: char| {
+            list.handle_event(&fruits, &InputEvent::Key(KeyEvent::from_char(character)))
+        };
+
+        typed(&mut list, 'b');
+        assert_eq!(list.selected(), 1);
+        typed(&mut list, 'l');
+        assert_eq!(list.selected(), 2);
+        list.handle_event(&fruits, &key(VK_HOME, 0));
+        typed(&mut list, 'b');
+        typed(&mut list, 'b');
+        typed(&mut list, 'b');
+        assert_eq!(list.selected(), 4);
+        assert_eq!(list.scroll(), 2);
+    }
+
+    #[test]
+    fn selects_with_the_mouse_and_scrolls_with_the_wheel() {
+        let source: Numbers = Numbers(50);
+        let mut list: ListBox = ListBox::new((2, 2, 12, 5));
+        let click = MouseEvent::new(Coord(4, 4), FROM_LEFT_1ST_BUTTON_PRESSED, 0);
+        let wheel_down = MouseEvent::new(Coord(4, 4), 0xFF88 << 16, MOUSE_WHEELED);
+
+        list.handle_event(&source, &InputEvent::Mouse(click));
+        assert_eq!(list.selected(), 2);
+        list.handle_event(&source, &InputEvent::Mouse(wheel_down));
+        assert_eq!(list.scroll(), 3);
+        let double = MouseEvent::new(Coord(4, 2), FROM_LEFT_1ST_BUTTON_PRESSED, DOUBLE_CLICK);
+        assert_eq!(
+            list.handle_event(&source, &InputEvent::Mouse(double)),
+            Some(ListBoxEvent::Activated(3))
+        );
+    }
+}
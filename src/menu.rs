@@ -0,0 +1,1049 @@
+use crate::constants::*;
+use crate::frame_style::FrameStyle;
+use crate::geometry::Coord;
+use crate::input::{InputEvent, KeyEvent, MouseEvent};
+use crate::rusted::Rusted;
+use crate::theme::Theme;
+use crate::window::Window;
+use crate::window_options::WindowOptions;
+
+/// what choosing a [MenuItem] does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuItemKind {
+    /// reports the command id
+    Command(u32),
+    /// a line between groups of items, it cannot be chosen
+    Separator,
+    /// opens another menu beside this one
+    Submenu(Menu),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuItem {
+    /// the text shown, & marks the hotkey after it and && is a literal &
+    pub label: String,
+    pub kind: MenuItemKind,
+    /// the keyboard shortcut shown right-aligned, such as "Ctrl+S"
+    pub shortcut: Option<String>,
+    pub enabled: bool,
+    pub checked: bool,
+}
+
+impl MenuItem {
+    pub fn command(label: &str, id: u32) -> Self {
+        Self::util_new(label, MenuItemKind::Command(id))
+    }
+
+    pub fn separator() -> Self {
+        Self::util_new("", MenuItemKind::Separator)
+    }
+
+    pub fn submenu(label: &str, menu: Menu) -> Self {
+        Self::util_new(label, MenuItemKind::Submenu(menu))
+    }
+
+    pub fn shortcut(mut self, shortcut: &str) -> Self {
+        self.shortcut = Some(shortcut.to_string());
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    /// gets the lowercase hotkey marked in the label
+    pub fn hotkey(&self) -> Option<char> {
+        util_hotkey(&self.label)
+    }
+
+    pub fn is_separator(&self) -> bool {
+        self.kind == MenuItemKind::Separator
+    }
+
+    fn util_new(label: &str, kind: MenuItemKind) -> Self {
+        Self {
+            label: label.to_string(),
+            kind,
+            shortcut: None,
+            enabled: true,
+            checked: false,
+        }
+    }
+}
+
+/// the items of a drop-down, popup or submenu
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Menu {
+    pub items: Vec<MenuItem>,
+}
+
+impl Menu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn item(mut self, item: MenuItem) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    pub fn separator(self) -> Self {
+        self.item(MenuItem::separator())
+    }
+
+    /// gets the width and height of the menu including its frame
+    pub fn size(&self) -> (i32, i32) {
+        let label: usize = self
+            .items
+            .iter()
+            .map(|item| util_parse_label(&item.label).0.chars().count())
+            .max()
+            .unwrap_or(0);
+        let shortcut: usize = self
+            .items
+            .iter()
+            .filter_map(|item| item.shortcut.as_ref())
+            .map(|shortcut| shortcut.chars().count() + 2)
+            .max()
+            .unwrap_or(0);
+        // a space, the check mark column and a space before the label, a space after it
+        let inner: usize = 3 + label + 1 + shortcut + if self.util_has_submenu() { 2 } else { 0 };
+        (inner as i32 + 2, self.items.len() as i32 + 2)
+    }
+
+    fn util_has_submenu(&self) -> bool {
+        self.items
+            .iter()
+            .any(|item| matches!(item.kind, MenuItemKind::Submenu(_)))
+    }
+
+    /// finds the enabled item with the hotkey
+    fn util_find_hotkey(&self, hotkey: char) -> Option<usize> {
+        self.items
+            .iter()
+            .position(|item| item.enabled && item.hotkey() == Some(hotkey))
+    }
+}
+
+/// what a [MenuBar] did with an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuEvent {
+    Opened,
+    /// an enabled command was chosen and the menus were closed
+    Command(u32),
+    /// the menus were closed without choosing a command
+    Closed,
+}
+
+/// a menu on screen with the cells saved under it
+#[derive(Debug, Clone)]
+struct OpenMenu {
+    menu: Menu,
+    window: Window,
+    rect: (i32, i32, i32, i32),
+    cursor: usize,
+    /// the first item shown when the menu is taller than the console
+    scroll: usize,
+}
+
+/// what the open menus want done after an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuStep {
+    Stay,
+    Command(u32),
+    Close,
+    /// Left was pressed in the root menu
+    Left,
+    /// Right was pressed on an item without a submenu
+    Right,
+}
+
+/// the menus currently shown, each submenu after the menu it was opened from
+#[derive(Debug, Clone, Default)]
+struct MenuStack {
+    open: Vec<OpenMenu>,
+}
+
+impl MenuStack {
+    /// opens the menu at the preferred x and y of each pair, taking the flipped one when the
+    /// menu does not fit. a menu taller than the console scrolls
+    fn push(&mut self, ctx: &mut Rusted, menu: Menu, x: (i32, i32), y: (i32, i32)) {
+        let (columns, rows) = (ctx.console.size.0 as i32, ctx.console.size.1 as i32);
+        let (w, h) = menu.size();
+        let h: i32 = h.min(rows).max(3);
+        let rect: (i32, i32, i32, i32) = (util_fit(x, w, columns), util_fit(y, h, rows), w, h);
+        let theme: Theme = *ctx.theme();
+        let options: WindowOptions = WindowOptions::new(rect)
+            .colors(theme.menu.0, theme.menu.1)
+            .frame(FrameStyle::Single)
+            .shadow(theme.shadow);
+        ctx.push_state();
+        let (window, _) = ctx.open_window(&options);
+        ctx.pop_state();
+        let cursor: usize = menu
+            .items
+            .iter()
+            .position(|item| !item.is_separator())
+            .unwrap_or(0);
+        let open: OpenMenu = OpenMenu {
+            menu,
+            window,
+            rect,
+            cursor,
+            scroll: 0,
+        };
+        util_draw_items(ctx, &open);
+        self.open.push(open);
+    }
+
+    /// opens the submenu beside the cursor of the top menu, to its right or else to its left, and
+    /// downwards from the cursor row or else upwards
+    fn push_submenu(&mut self, ctx: &mut Rusted) {
+        let Some(top) = self.open.last() else {
+            return;
+        };
+        let item: &MenuItem = &top.menu.items[top.cursor];
+        let MenuItemKind::Submenu(menu) = &item.kind else {
+            return;
+        };
+        if !item.enabled {
+            return;
+        }
+        let (x, y, w, _) = top.rect;
+        let (width, height) = menu.size();
+        let row: i32 = y + (top.cursor - top.scroll) as i32;
+        let menu: Menu = menu.clone();
+        self.push(ctx, menu, (x + w, x - width), (row, row + 3 - height));
+    }
+
+    fn pop(&mut self, ctx: &mut Rusted) {
+        if let Some(open) = self.open.pop() {
+            ctx.close_window(&open.window);
+        }
+    }
+
+    fn clear(&mut self, ctx: &mut Rusted) {
+        while !self.open.is_empty() {
+            self.pop(ctx);
+        }
+    }
+
+    fn handle_key(&mut self, ctx: &mut Rusted, key: &KeyEvent) -> MenuStep {
+        let Some(top) = self.open.last() else {
+            return MenuStep::Stay;
+        };
+        if top.menu.items.is_empty() {
+            return match key.virtual_key_code {
+                VK_LEFT if self.open.len() == 1 => MenuStep::Left,
+                VK_RIGHT => MenuStep::Right,
+                VK_LEFT | VK_ESCAPE if self.open.len() > 1 => {
+                    self.pop(ctx);
+                    MenuStep::Stay
+                }
+                VK_ESCAPE => MenuStep::Close,
+                _ => MenuStep::Stay,
+            };
+        }
+        let cursor: usize = top.cursor;
+        match key.virtual_key_code {
+            VK_UP => self.util_move(ctx, false, cursor),
+            VK_DOWN => self.util_move(ctx, true, cursor),
+            VK_HOME => self.util_move(ctx, true, top.menu.items.len() - 1),
+            VK_END => self.util_move(ctx, false, 0),
+            VK_RIGHT => match top.menu.items[cursor].kind {
+                MenuItemKind::Submenu(_) if top.menu.items[cursor].enabled => {
+                    self.push_submenu(ctx)
+                }
+                _ => return MenuStep::Right,
+            },
+            VK_LEFT if self.open.len() > 1 => self.pop(ctx),
+            VK_LEFT => return MenuStep::Left,
+            VK_ESCAPE if self.open.len() > 1 => self.pop(ctx),
+            VK_ESCAPE => return MenuStep::Close,
+            VK_RETURN | VK_SPACE => return self.util_activate(ctx, cursor),
+            _ => {
+                let found: Option<usize> =
+                    util_key_char(key).and_then(|hotkey| top.menu.util_find_hotkey(hotkey));
+                if let Some(index) = found {
+                    self.util_set_cursor(ctx, index);
+                    return self.util_activate(ctx, index);
+                }
+            }
+        }
+        MenuStep::Stay
+    }
+
+    /// highlights items under the mouse and chooses them with a click. None when the mouse is
+    /// outside every open menu
+    fn handle_mouse(&mut self, ctx: &mut Rusted, mouse: &MouseEvent) -> Option<MenuStep> {
+        let (mx, my) = (mouse.position.0 as i32, mouse.position.1 as i32);
+        let depth: usize = self.open.iter().rposition(|open| {
+            let (x, y, w, h) = open.rect;
+            mx >= x && mx < x + w && my >= y && my < y + h
+        })?;
+        let (x, y, w, h) = self.open[depth].rect;
+        let row: i32 = my - y - 1;
+        if row < 0 || row >= h - 2 || mx == x || mx == x + w - 1 {
+            return Some(MenuStep::Stay);
+        }
+        let index: usize = self.open[depth].scroll + row as usize;
+        if index >= self.open[depth].menu.items.len() {
+            return Some(MenuStep::Stay);
+        }
+        let pressed: bool = mouse.left_button() && !mouse.moved();
+        if self.open[depth].menu.items[index].is_separator() || !(pressed || mouse.moved()) {
+            return Some(MenuStep::Stay);
+        }
+        while self.open.len() > depth + 1 {
+            self.pop(ctx);
+        }
+        self.util_set_cursor(ctx, index);
+        if pressed {
+            return Some(self.util_activate(ctx, index));
+        }
+        Some(MenuStep::Stay)
+    }
+
+    /// chooses the item of the top menu, opening its submenu or reporting its command
+    fn util_activate(&mut self, ctx: &mut Rusted, index: usize) -> MenuStep {
+        let item: &MenuItem = &self.open.last().unwrap().menu.items[index];
+        match item.kind {
+            _ if !item.enabled => MenuStep::Stay,
+            MenuItemKind::Command(id) => MenuStep::Command(id),
+            MenuItemKind::Submenu(_) => {
+                self.push_submenu(ctx);
+                MenuStep::Stay
+            }
+            MenuItemKind::Separator => MenuStep::Stay,
+        }
+    }
+
+    /// moves the cursor of the top menu to the next item from start that is not a separator,
+    /// wrapping around
+    fn util_move(&mut self, ctx: &mut Rusted, down: bool, start: usize) {
+        let items: &Vec<MenuItem> = &self.open.last().unwrap().menu.items;
+        let count: usize = items.len();
+        let found: Option<usize> = (1..=count)
+            .map(|step| {
+                if down {
+                    (start + step) % count
+                } else {
+                    (start + count * 2 - step) % count
+                }
+            })
+            .find(|&index| !items[index].is_separator());
+        if let Some(index) = found {
+            self.util_set_cursor(ctx, index);
+        }
+    }
+
+    fn util_set_cursor(&mut self, ctx: &mut Rusted, index: usize) {
+        let top: &mut OpenMenu = self.open.last_mut().unwrap();
+        if top.cursor != index {
+            let visible: usize = (top.rect.3 - 2) as usize;
+            top.cursor = index;
+            if index < top.scroll {
+                top.scroll = index;
+            } else if index >= top.scroll + visible {
+                top.scroll = index + 1 - visible;
+            }
+            util_draw_items(ctx, top);
+        }
+    }
+}
+
+/// the classic top menu bar, its titles open drop-down menus that restore the screen when closed
+#[derive(Debug, Clone)]
+pub struct MenuBar {
+    /// the row the bar is drawn on
+    pub y: i32,
+    #[doc(hidden)]
+    menus: Vec<(String, Menu)>,
+    /// the menu whose title is highlighted while it is open
+    #[doc(hidden)]
+    active: Option<usize>,
+    #[doc(hidden)]
+    stack: MenuStack,
+}
+
+impl MenuBar {
+    pub fn new(y: i32) -> Self {
+        Self {
+            y,
+            menus: vec![],
+            active: None,
+            stack: MenuStack::default(),
+        }
+    }
+
+    /// adds a menu, & in the title marks the Alt hotkey that opens it
+    pub fn menu(mut self, title: &str, menu: Menu) -> Self {
+        self.menus.push((title.to_string(), menu));
+        self
+    }
+
+    /// gets a menu to change its items, the change shows the next time it opens
+    pub fn menu_mut(&mut self, index: usize) -> Option<&mut Menu> {
+        self.menus.get_mut(index).map(|(_, menu)| menu)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.active.is_some()
+    }
+
+    pub fn active(&self) -> Option<usize> {
+        self.active
+    }
+
+    /// gets x, y, w, h of the title of the menu, padded with a space on both sides
+    pub fn title_rect(&self, index: usize) -> (i32, i32, i32, i32) {
+        let width = |title: &String| util_parse_label(title).0.chars().count() as i32 + 2;
+        let x: i32 = 1 + self.menus[..index]
+            .iter()
+            .map(|(title, _)| width(title))
+            .sum::<i32>();
+        (x, self.y, width(&self.menus[index].0), 1)
+    }
+
+    /// draws the bar across the console with the menu colors of the theme
+    pub fn draw(&self, ctx: &mut Rusted) {
+        let theme: Theme = *ctx.theme();
+        ctx.push_state();
+        ctx.set_fgcolor(theme.menu.0);
+        ctx.set_bgcolor(theme.menu.1);
+        ctx.outchars(0, self.y, &" ".repeat(ctx.console.size.0 as usize));
+        for (index, (title, _)) in self.menus.iter().enumerate() {
+            let (x, y, _, _) = self.title_rect(index);
+            let (text, hotkey) = util_parse_label(title);
+            let active: bool = self.active == Some(index);
+            let (fgc, bgc) = if active { theme.selected } else { theme.menu };
+            ctx.set_fgcolor(fgc);
+            ctx.set_bgcolor(bgc);
+            ctx.outchars(x, y, &format!(" {} ", text));
+            if let (false, Some(hotkey)) = (active, hotkey) {
+                ctx.set_fgcolor(theme.hotkey);
+                ctx.outchar(x + 1 + hotkey as i32, y, text.chars().nth(hotkey).unwrap());
+            }
+        }
+        ctx.pop_state();
+    }
+
+    /// closes any open menu and opens the menu below its title
+    pub fn open(&mut self, ctx: &mut Rusted, index: usize) {
+        if index >= self.menus.len() {
+            return;
+        }
+        self.stack.clear(ctx);
+        self.active = Some(index);
+        self.draw(ctx);
+        let (x, y, _, _) = self.title_rect(index);
+        let menu: Menu = self.menus[index].1.clone();
+        self.stack.push(ctx, menu, (x, x), (y + 1, y + 1));
+    }
+
+    /// closes the open menus, restoring the cells under them
+    pub fn close(&mut self, ctx: &mut Rusted) {
+        self.stack.clear(ctx);
+        if self.active.take().is_some() {
+            self.draw(ctx);
+        }
+    }
+
+    /// opens a menu with Alt and its hotkey, F10 or a click on its title. while a menu is open
+    /// every key is used by it: arrows move within and between menus, Enter and hotkeys choose
+    /// and Escape backs out
+    pub fn handle_event(&mut self, ctx: &mut Rusted, event: &InputEvent) -> Option<MenuEvent> {
+        match event {
+            InputEvent::Key(key) if key.key_down => self.util_handle_key(ctx, key),
+            InputEvent::Mouse(mouse) => self.util_handle_mouse(ctx, mouse),
+            _ => None,
+        }
+    }
+
+    fn util_handle_key(&mut self, ctx: &mut Rusted, key: &KeyEvent) -> Option<MenuEvent> {
+        let title: Option<usize> = match (key.alt(), util_key_char(key)) {
+            (true, Some(hotkey)) => self
+                .menus
+                .iter()
+                .position(|(title, _)| util_hotkey(title) == Some(hotkey)),
+            _ => None,
+        };
+        if !self.is_open() {
+            let index: usize = match (title, key.virtual_key_code) {
+                (Some(index), _) => index,
+                (None, VK_F10) if !self.menus.is_empty() => 0,
+                _ => return None,
+            };
+            self.open(ctx, index);
+            return Some(MenuEvent::Opened);
+        }
+        if let Some(index) = title {
+            if self.active != Some(index) {
+                self.open(ctx, index);
+            }
+            return None;
+        }
+        if key.virtual_key_code == VK_F10 {
+            self.close(ctx);
+            return Some(MenuEvent::Closed);
+        }
+        let step: MenuStep = self.stack.handle_key(ctx, key);
+        self.util_step(ctx, step)
+    }
+
+    fn util_handle_mouse(&mut self, ctx: &mut Rusted, mouse: &MouseEvent) -> Option<MenuEvent> {
+        if let Some(step) = self.stack.handle_mouse(ctx, mouse) {
+            return self.util_step(ctx, step);
+        }
+        if !mouse.left_button() || mouse.moved() {
+            return None;
+        }
+        let (mx, my) = (mouse.position.0 as i32, mouse.position.1 as i32);
+        let title: Option<usize> = (0..self.menus.len()).find(|&index| {
+            let (x, y, w, _) = self.title_rect(index);
+            my == y && mx >= x && mx < x + w
+        });
+        match title {
+            Some(index) if self.active != Some(index) => {
+                self.open(ctx, index);
+                Some(MenuEvent::Opened)
+            }
+            _ if self.is_open() => {
+                self.close(ctx);
+                Some(MenuEvent::Closed)
+            }
+            _ => None,
+        }
+    }
+
+    fn util_step(&mut self, ctx: &mut Rusted, step: MenuStep) -> Option<MenuEvent> {
+        let (Some(active), count) = (self.active, self.menus.len()) else {
+            return None;
+        };
+        match step {
+            MenuStep::Stay => None,
+            MenuStep::Command(id) => {
+                self.close(ctx);
+                Some(MenuEvent::Command(id))
+            }
+            MenuStep::Close => {
+                self.close(ctx);
+                Some(MenuEvent::Closed)
+            }
+            MenuStep::Left => {
+                self.open(ctx, (active + count - 1) % count);
+                None
+            }
+            MenuStep::Right => {
+                self.open(ctx, (active + 1) % count);
+                None
+            }
+        }
+    }
+}
+
+/// a context menu opened at the mouse with a right click, or beside the anchor with Shift+F10 or
+/// the Applications key. it flips or shifts to stay on the console and scrolls when it is taller
+#[derive(Debug, Clone)]
+pub struct PopupMenu {
+    pub menu: Menu,
+    /// x, y, w, h of the widget the keyboard opens the menu below, the console cursor when None
+    pub anchor: Option<(i32, i32, i32, i32)>,
+    #[doc(hidden)]
+    stack: MenuStack,
+}
+
+impl PopupMenu {
+    pub fn new(menu: Menu) -> Self {
+        Self {
+            menu,
+            anchor: None,
+            stack: MenuStack::default(),
+        }
+    }
+
+    pub fn anchor(mut self, rect: (i32, i32, i32, i32)) -> Self {
+        self.anchor = Some(rect);
+        self
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.stack.open.is_empty()
+    }
+
+    /// opens the menu right of and below the position, or left of and above it on the sides
+    /// where it does not fit
+    pub fn open_at(&mut self, ctx: &mut Rusted, position: Coord) {
+        let (x, y) = (position.0 as i32, position.1 as i32);
+        let (w, h) = self.menu.size();
+        self.util_open(ctx, (x, x + 1 - w), (y, y + 1 - h));
+    }
+
+    /// opens the menu below the rect, or above it when it does not fit below, lined up with its
+    /// left edge or else its right edge
+    pub fn open_beside(&mut self, ctx: &mut Rusted, rect: (i32, i32, i32, i32)) {
+        let (x, y, w, h) = rect;
+        let (width, height) = self.menu.size();
+        self.util_open(ctx, (x, x + w - width), (y + h, y - height));
+    }
+
+    /// closes the menu and its submenus, restoring the cells under them
+    pub fn close(&mut self, ctx: &mut Rusted) {
+        self.stack.clear(ctx);
+    }
+
+    /// while closed, opens on a right click, Shift+F10 or the Applications key. while open every
+    /// key is used by it and a click outside closes it
+    pub fn handle_event(&mut self, ctx: &mut Rusted, event: &InputEvent) -> Option<MenuEvent> {
+        if !self.is_open() {
+            return self.util_handle_trigger(ctx, event);
+        }
+        let step: MenuStep = match event {
+            InputEvent::Key(key) if key.key_down => self.stack.handle_key(ctx, key),
+            InputEvent::Mouse(mouse) => match self.stack.handle_mouse(ctx, mouse) {
+                Some(step) => step,
+                None if (mouse.left_button() || mouse.right_button()) && !mouse.moved() => {
+                    MenuStep::Close
+                }
+                None => MenuStep::Stay,
+            },
+            _ => MenuStep::Stay,
+        };
+        match step {
+            MenuStep::Command(id) => {
+                self.close(ctx);
+                Some(MenuEvent::Command(id))
+            }
+            MenuStep::Close => {
+                self.close(ctx);
+                Some(MenuEvent::Closed)
+            }
+            _ => None,
+        }
+    }
+
+    fn util_handle_trigger(&mut self, ctx: &mut Rusted, event: &InputEvent) -> Option<MenuEvent> {
+        match event {
+            InputEvent::Mouse(mouse) if mouse.right_button() && !mouse.moved() => {
+                self.open_at(ctx, mouse.position);
+            }
+            InputEvent::Key(key)
+                if key.key_down
+                    && (key.virtual_key_code == VK_APPS
+                        || (key.virtual_key_code == VK_F10 && key.shift())) =>
+            {
+                match self.anchor {
+                    Some(rect) => self.open_beside(ctx, rect),
+                    None => {
+                        let cursor: Coord = ctx.console.cursor;
+                        self.open_at(ctx, cursor);
+                    }
+                }
+            }
+            _ => return None,
+        }
+        Some(MenuEvent::Opened)
+    }
+
+    fn util_open(&mut self, ctx: &mut Rusted, x: (i32, i32), y: (i32, i32)) {
+        self.stack.clear(ctx);
+        let menu: Menu = self.menu.clone();
+        self.stack.push(ctx, menu, x, y);
+    }
+}
+
+/// draws the rows of an open menu inside its frame, the cursor row with the selected colors, and
+/// pointers in the frame when items are scrolled out of view
+fn util_draw_items(ctx: &mut Rusted, open: &OpenMenu) {
+    let theme: Theme = *ctx.theme();
+    let (x, y, w, h) = open.rect;
+    let visible: usize = (h - 2) as usize;
+    let inner: usize = (w - 2) as usize;
+    // shortcuts end before the pointer column when any item has a submenu
+    let end: usize = inner - 1 - if open.menu.util_has_submenu() { 2 } else { 0 };
+    ctx.push_state();
+    let shown = open.menu.items.iter().enumerate().skip(open.scroll);
+    for (row, (index, item)) in shown.take(visible).enumerate() {
+        let row_y: i32 = y + 1 + row as i32;
+        if item.is_separator() {
+            ctx.set_fgcolor(theme.menu.0);
+            ctx.set_bgcolor(theme.menu.1);
+            let line: String = BOX_HORIZONTAL_SINGLE.to_string().repeat(inner);
+            let separator: String =
+                format!("{}{}{}", BOX_LEFTTEE_SINGLE, line, BOX_RIGHTTEE_SINGLE);
+            ctx.outchars(x, row_y, &separator);
+            continue;
+        }
+        let (text, hotkey) = util_parse_label(&item.label);
+        let mut line: Vec<char> = vec![' '; inner];
+        if item.checked {
+            line[1] = CHECK_MARK;
+        }
+        for (index, character) in text.chars().enumerate().take(inner.saturating_sub(4)) {
+            line[3 + index] = character;
+        }
+        if let Some(shortcut) = &item.shortcut {
+            let start: usize = end - shortcut.chars().count();
+            for (index, character) in shortcut.chars().enumerate() {
+                line[start + index] = character;
+            }
+        }
+        if let MenuItemKind::Submenu(_) = item.kind {
+            line[inner - 2] = RIGHT_POINTER;
+        }
+        let selected: bool = index == open.cursor;
+        let (fgc, bgc) = if selected {
+            theme.selected
+        } else if !item.enabled {
+            (theme.disabled.0, theme.menu.1)
+        } else {
+            theme.menu
+        };
+        ctx.set_fgcolor(fgc);
+        ctx.set_bgcolor(bgc);
+        ctx.outchars(x + 1, row_y, &line.iter().collect::<String>());
+        if let (false, true, Some(hotkey)) = (selected, item.enabled, hotkey) {
+            ctx.set_fgcolor(theme.hotkey);
+            ctx.outchar(x + 4 + hotkey as i32, row_y, line[3 + hotkey]);
+        }
+    }
+    ctx.set_fgcolor(theme.menu.0);
+    ctx.set_bgcolor(theme.menu.1);
+    let (up, down) = (
+        open.scroll > 0,
+        open.scroll + visible < open.menu.items.len(),
+    );
+    ctx.outchar(
+        x + w - 2,
+        y,
+        if up {
+            UP_POINTER
+        } else {
+            BOX_HORIZONTAL_SINGLE
+        },
+    );
+    ctx.outchar(
+        x + w - 2,
+        y + h - 1,
+        if down {
+            DOWN_POINTER
+        } else {
+            BOX_HORIZONTAL_SINGLE
+        },
+    );
+    ctx.pop_state();
+}
+
+/// gets the start of a span of size within 0..limit, preferred when it fits, else flipped when
+/// that fits, else preferred shifted to fit
+fn util_fit((preferred, flipped): (i32, i32), size: i32, limit: i32) -> i32 {
+    if preferred >= 0 && preferred + size <= limit {
+        preferred
+    } else if flipped >= 0 && flipped + size <= limit {
+        flipped
+    } else {
+        preferred.min(limit - size).max(0)
+    }
+}
+
+/// splits a label into the text shown and the index of its hotkey within that text
+fn util_parse_label(label: &str) -> (String, Option<usize>) {
+    let mut text: String = String::new();
+    let mut hotkey: Option<usize> = None;
+    let mut characters = label.chars();
+    while let Some(character) = characters.next() {
+        if character != '&' {
+            text.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('&') => text.push('&'),
+            Some(next) => {
+                hotkey = hotkey.or(Some(text.chars().count()));
+                text.push(next);
+            }
+            None => {}
+        }
+    }
+    (text, hotkey)
+}
+
+fn util_hotkey(label: &str) -> Option<char> {
+    let (text, hotkey) = util_parse_label(label);
+    text.chars()
+        .nth(hotkey?)
+        .map(|character| character.to_ascii_lowercase())
+}
+
+/// gets the lowercase letter or digit of a key, from its virtual key code when Alt or Ctrl left
+/// no character
+fn util_key_char(key: &KeyEvent) -> Option<char> {
+    if key.character > ' ' {
+        return Some(key.character.to_ascii_lowercase());
+    }
+    match key.virtual_key_code {
+        VK_0..=VK_9 | VK_A..=VK_Z => {
+            Some((key.virtual_key_code as u8 as char).to_ascii_lowercase())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod menu_navigation {
+    use crate::test_util::{cells, key};
+    use crate::*;
+
+    fn file_menu() -> Menu {
+        Menu::new()
+            .item(MenuItem::command("&New", 1))
+            .item(MenuItem::command("&Save", 2).shortcut("Ctrl+S"))
+            .separator()
+            .item(MenuItem::command("&Wrap", 3).checked(true))
+            .item(MenuItem::submenu(
+                "&Recent",
+                Menu::new()
+                    .item(MenuItem::command("a.txt", 10))
+                    .item(MenuItem::command("b.txt", 11)),
+            ))
+            .item(MenuItem::command("E&xit", 9).enabled(false))
+    }
+
+    fn bar() -> MenuBar {
+        MenuBar::new(0)
+            .menu("&File", file_menu())
+            .menu("&Edit", Menu::new().item(MenuItem::command("&Undo", 20)))
+    }
+
+    #[test]
+    fn draws_titles_items_separators_and_shortcuts() {
+        let mut rusted = Rusted::new();
+        let mut bar: MenuBar = bar();
+        bar.draw(&mut rusted);
+        assert_eq!(cells(&rusted, 0, 0, 13), "  File  Edit ");
+        assert_eq!(
+            rusted.console.get_cell(Coord(2, 0)).unwrap().1,
+            Rusted::color_attribute(1, 7)
+        );
+
+        assert_eq!(
+            bar.handle_event(&mut rusted, &key(VK_F, LEFT_ALT_PRESSED)),
+            Some(MenuEvent::Opened)
+        );
+        assert_eq!(bar.active(), Some(0));
+        assert_eq!(
+            cells(&rusted, 1, 3, 22),
+            "\u{2502}   Save    Ctrl+S   \u{2502}"
+        );
+        assert_eq!(
+            cells(&rusted, 1, 4, 22),
+            format!("\u{251C}{}\u{2524}", "\u{2500}".repeat(20))
+        );
+        assert_eq!(cells(&rusted, 2, 5, 8), " \u{221A} Wrap ");
+        assert_eq!(cells(&rusted, 2, 6, 20), "   Recent         \u{25BA} ");
+        assert_eq!(
+            rusted.console.get_cell(Coord(3, 7)).unwrap().1,
+            Rusted::color_attribute(8, 7)
+        );
+    }
+
+    #[test]
+    fn moves_between_items_and_menus_and_restores_the_screen() {
+        let mut rusted = Rusted::new();
+        let mut bar: MenuBar = bar();
+        bar.draw(&mut rusted);
+        let before: Vec<CharInfo> = rusted.console.buffer.clone();
+
+        bar.handle_event(&mut rusted, &key(VK_F10, 0));
+        bar.handle_event(&mut rusted, &key(VK_DOWN, 0));
+        bar.handle_event(&mut rusted, &key(VK_DOWN, 0));
+        bar.handle_event(&mut rusted, &key(VK_DOWN, 0));
+        bar.handle_event(&mut rusted, &key(VK_RIGHT, 0));
+        assert_eq!(cells(&rusted, 23, 6, 11), "\u{2502}   a.txt \u{2502}");
+        assert_eq!(bar.handle_event(&mut rusted, &key(VK_DOWN, 0)), None);
+        assert_eq!(
+            bar.handle_event(&mut rusted, &key(VK_RETURN, 0)),
+            Some(MenuEvent::Command(11))
+        );
+        assert!(!bar.is_open());
+        assert_eq!(rusted.console.buffer, before);
+
+        bar.handle_event(&mut rusted, &key(VK_F10, 0));
+        bar.handle_event(&mut rusted, &key(VK_LEFT, 0));
+        assert_eq!(bar.active(), Some(1));
+        bar.handle_event(&mut rusted, &key(VK_RIGHT, 0));
+        assert_eq!(bar.active(), Some(0));
+        assert_eq!(
+            bar.handle_event(&mut rusted, &key(VK_ESCAPE, 0)),
+            Some(MenuEvent::Closed)
+        );
+        assert_eq!(rusted.console.buffer, before);
+    }
+
+    #[test]
+    fn chooses_with_hotkeys_and_ignores_disabled_items() {
+        let mut rusted = Rusted::new();
+        let mut bar: MenuBar = bar();
+        bar.handle_event(&mut rusted, &key(VK_F, LEFT_ALT_PRESSED));
+
+        assert_eq!(bar.handle_event(&mut rusted, &key(VK_X, 0)), None);
+        bar.handle_event(&mut rusted, &key(VK_UP, 0));
+        assert_eq!(bar.handle_event(&mut rusted, &key(VK_RETURN, 0)), None);
+        assert!(bar.is_open());
+        assert_eq!(
+            bar.handle_event(&mut rusted, &InputEvent::Key(KeyEvent::from_char('s'))),
+            Some(MenuEvent::Command(2))
+        );
+    }
+
+    #[test]
+    fn opens_empty_menus_without_items_to_choose() {
+        let mut rusted = Rusted::new();
+        let mut bar: MenuBar = MenuBar::new(0)
+            .menu("&File", Menu::new())
+            .menu("&Edit", Menu::new());
+        bar.handle_event(&mut rusted, &key(VK_F10, 0));
+
+        for code in [VK_HOME, VK_END, VK_UP, VK_DOWN, VK_RETURN, VK_SPACE] {
+            assert_eq!(bar.handle_event(&mut rusted, &key(code, 0)), None);
+        }
+        let click = MouseEvent::new(Coord(3, 2), FROM_LEFT_1ST_BUTTON_PRESSED, 0);
+        assert_eq!(
+            bar.handle_event(&mut rusted, &InputEvent::Mouse(click)),
+            None
+        );
+        bar.handle_event(&mut rusted, &key(VK_RIGHT, 0));
+        assert_eq!(bar.active(), Some(1));
+        assert_eq!(
+            bar.handle_event(&mut rusted, &key(VK_ESCAPE, 0)),
+            Some(MenuEvent::Closed)
+        );
+    }
+
+    #[test]
+    fn opens_and_chooses_with_the_mouse() {
+        let mut rusted = Rusted::new();
+        let mut bar: MenuBar = bar();
+        let click = |x: u16, y: u16| {
+            InputEvent::Mouse(MouseEvent::new(
+                Coord(x, y),
+                FROM_LEFT_1ST_BUTTON_PRESSED,
+                0,
+            ))
+        };
+
+        assert_eq!(
+            bar.handle_event(&mut rusted, &click(8, 0)),
+            Some(MenuEvent::Opened)
+        );
+        assert_eq!(bar.active(), Some(1));
+        assert_eq!(
+            bar.handle_event(&mut rusted, &click(10, 2)),
+            Some(MenuEvent::Command(20))
+        );
+        bar.handle_event(&mut rusted, &click(2, 0));
+        assert_eq!(
+            bar.handle_event(&mut rusted, &click(40, 20)),
+            Some(MenuEvent::Closed)
+        );
+    }
+}
+
+#[cfg(test)]
+mod popup_menu {
+    use crate::test_util::key;
+    use crate::*;
+
+    fn menu(count: u32) -> Menu {
+        (0..count).fold(Menu::new(), |menu, id| {
+            menu.item(MenuItem::command(&format!("Item {:02}", id), id))
+        })
+    }
+
+    fn right_click(x: u16, y: u16) -> InputEvent {
+        InputEvent::Mouse(MouseEvent::new(Coord(x, y), RIGHTMOST_BUTTON_PRESSED, 0))
+    }
+
+    fn corner(rusted: &Rusted, x: u16, y: u16) -> char {
+        rusted.console.get_cell(Coord(x, y)).unwrap().0
+    }
+
+    #[test]
+    fn flips_and_shifts_to_fit_the_console() {
+        let mut rusted = Rusted::new();
+        let mut popup: PopupMenu = PopupMenu::new(menu(3));
+        let (w, h) = popup.menu.size();
+        assert_eq!((w, h), (13, 5));
+
+        assert_eq!(
+            popup.handle_event(&mut rusted, &right_click(10, 5)),
+            Some(MenuEvent::Opened)
+        );
+        assert_eq!(corner(&rusted, 10, 5), BOX_TOPLEFT_SINGLE);
+        popup.close(&mut rusted);
+
+        popup.open_at(&mut rusted, Coord(75, 23));
+        assert_eq!(corner(&rusted, 75, 23), BOX_BOTRIGHT_SINGLE);
+        popup.close(&mut rusted);
+
+        popup.open_beside(&mut rusted, (70, 22, 8, 1));
+        assert_eq!(corner(&rusted, 65, 17), BOX_TOPLEFT_SINGLE);
+        assert_eq!(corner(&rusted, 77, 21), BOX_BOTRIGHT_SINGLE);
+        assert_eq!(
+            popup.handle_event(&mut rusted, &key(VK_ESCAPE, 0)),
+            Some(MenuEvent::Closed)
+        );
+    }
+
+    #[test]
+    fn scrolls_menus_taller_than_the_console() {
+        let mut rusted = Rusted::new();
+        let before: Vec<CharInfo> = rusted.console.buffer.clone();
+        let mut popup: PopupMenu = PopupMenu::new(menu(40)).anchor((0, 0, 1, 1));
+
+        popup.handle_event(&mut rusted, &key(VK_F10, SHIFT_PRESSED));
+        assert!(popup.is_open());
+        assert_eq!(corner(&rusted, 0, 0), BOX_TOPLEFT_SINGLE);
+        assert_eq!(corner(&rusted, 11, 24), DOWN_POINTER);
+
+        popup.handle_event(&mut rusted, &key(VK_END, 0));
+        assert_eq!(corner(&rusted, 11, 0), UP_POINTER);
+        assert_eq!(corner(&rusted, 11, 24), BOX_HORIZONTAL_SINGLE);
+        let last: String = (4..11).map(|x| corner(&rusted, x, 23)).collect();
+        assert_eq!(last, "Item 39");
+
+        assert_eq!(
+            popup.handle_event(&mut rusted, &key(VK_RETURN, 0)),
+            Some(MenuEvent::Command(39))
+        );
+        assert_eq!(rusted.console.buffer, before);
+    }
+
+    #[test]
+    fn lines_up_submenus_that_open_upwards_with_their_item() {
+        let mut rusted = Rusted::new();
+        let submenu: Menu = Menu::new()
+            .item(MenuItem::command("x1", 1))
+            .item(MenuItem::command("x2", 2))
+            .item(MenuItem::command("x3", 3));
+        let mut popup: PopupMenu = PopupMenu::new(
+            Menu::new()
+                .item(MenuItem::command("a", 0))
+                .item(MenuItem::submenu("Sub", submenu)),
+        );
+
+        popup.open_at(&mut rusted, Coord(0, 24));
+        popup.handle_event(&mut rusted, &key(VK_DOWN, 0));
+        popup.handle_event(&mut rusted, &key(VK_RIGHT, 0));
+
+        let item: String = (15..17).map(|x| corner(&rusted, x, 23)).collect();
+        assert_eq!(item, "x3");
+        assert_eq!(corner(&rusted, 11, 24), BOX_BOTLEFT_SINGLE);
+    }
+}
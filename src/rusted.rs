@@ -1,9 +1,46 @@
 use crate::*;
 
+/// handle to one of the screen buffers owned by a [Rusted] instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenBuffer(usize);
+
 pub struct Rusted {
+    /// the screen buffer that drawing calls currently write to
     pub console: Console,
     background_color: u16,
     foreground_color: u16,
+    #[doc(hidden)]
+    screen_buffers: Vec<Option<Console>>,
+    #[doc(hidden)]
+    target_buffer: usize,
+    #[doc(hidden)]
+    active_buffer: usize,
+    #[doc(hidden)]
+    follow_cursor: bool,
+    #[doc(hidden)]
+    history_offset: usize,
+    #[doc(hidden)]
+    highlights: Vec<HistoryMatch>,
+    #[doc(hidden)]
+    highlight_attribute: Attribute,
+    #[doc(hidden)]
+    clip: Rect,
+    #[doc(hidden)]
+    origin: (i32, i32),
+    #[doc(hidden)]
+    state_stack: Vec<DrawState>,
+    #[doc(hidden)]
+    theme: Theme,
+}
+
+/// the drawing state saved by [Rusted::push_state]
+#[derive(Debug, Clone, Copy)]
+struct DrawState {
+    clip: Rect,
+    origin: (i32, i32),
+    background_color: u16,
+    foreground_color: u16,
+    cursor: Coord,
 }
 
 impl Rusted {
@@ -12,179 +49,954 @@ impl Rusted {
             console: Console::new(80, 25),
             background_color: 0,
             foreground_color: FOREGROUND_RED | FOREGROUND_GREEN | FOREGROUND_BLUE,
+            // the slot of the target buffer is always empty, its console lives in `console`
+            screen_buffers: vec![None],
+            target_buffer: 0,
+            active_buffer: 0,
+            follow_cursor: false,
+            history_offset: 0,
+            highlights: vec![],
+            highlight_attribute: Attribute(
+                BACKGROUND_RED | BACKGROUND_GREEN | BACKGROUND_INTENSITY,
+            ),
+            clip: Rect(0, 0, u16::MAX, u16::MAX),
+            origin: (0, 0),
+            state_stack: vec![],
+            theme: Theme::default(),
         }
     }
-    pub fn screen80x25(&mut self) {
-        set_console_buffer_size(&mut self.console, Coord(80, 25));
+
+    /// the colors and frame the widgets draw with
+    pub fn theme(&self) -> &Theme {
+        &self.theme
     }
 
-    pub fn screen80x50(&mut self) {
-        set_console_buffer_size(&mut self.console, Coord(80, 50));
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
     }
 
-    pub fn set_bgcolor(&mut self, color: u16) {
-        let mut value: u16 = 0;
-        if color & 1 == 1 {
-            value |= BACKGROUND_RED;
+    /// gets the attribute of the foreground and background color indices
+    pub fn color_attribute(fgc: u16, bgc: u16) -> Attribute {
+        Attribute(util_foreground_bits(fgc) | util_background_bits(bgc))
+    }
+
+    /// recolors the w * h region at x, y with the focused colors of the theme, keeping the
+    /// characters
+    pub fn draw_focus_indicator(&mut self, rect: (i32, i32, i32, i32)) {
+        let state: DrawState = self.draw_state();
+        let (fgc, bgc) = self.theme.focused;
+        state.recolor(&mut self.console, rect, Self::color_attribute(fgc, bgc));
+    }
+
+    pub fn draw_focus_indicator_on<S: Surface + ?Sized>(
+        &self,
+        surface: &mut S,
+        rect: (i32, i32, i32, i32),
+    ) {
+        let (fgc, bgc) = self.theme.focused;
+        self.draw_state()
+            .recolor(surface, rect, Self::color_attribute(fgc, bgc));
+    }
+
+    /// saves the clip, origin, colors and cursor so that [Rusted::pop_state] can restore them
+    pub fn push_state(&mut self) {
+        self.state_stack.push(DrawState {
+            clip: self.clip,
+            origin: self.origin,
+            background_color: self.background_color,
+            foreground_color: self.foreground_color,
+            cursor: self.console.cursor,
+        });
+    }
+
+    /// restores the state saved by the matching [Rusted::push_state], false when nothing was saved
+    pub fn pop_state(&mut self) -> bool {
+        let Some(state) = self.state_stack.pop() else {
+            return false;
+        };
+        self.clip = state.clip;
+        self.origin = state.origin;
+        self.background_color = state.background_color;
+        self.foreground_color = state.foreground_color;
+        set_console_attribute(
+            &mut self.console,
+            Attribute(self.foreground_color | self.background_color),
+        );
+        set_console_cursor_position(&mut self.console, state.cursor);
+        true
+    }
+
+    /// moves the drawing origin by dx, dy, all drawing coordinates are relative to the origin
+    pub fn translate(&mut self, dx: i32, dy: i32) {
+        self.origin = (self.origin.0 + dx, self.origin.1 + dy);
+    }
+
+    /// gets the drawing origin in console coordinates
+    pub fn origin(&self) -> (i32, i32) {
+        self.origin
+    }
+
+    /// narrows the clip to the w * h region at x, y relative to the origin, drawing outside of
+    /// the clip is discarded. the clip can only shrink until the state is popped
+    pub fn set_clip(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        self.clip = self
+            .clip
+            .clip_to(&self.draw_state().surface_rect(x, y, w, h));
+    }
+
+    /// removes the clip, drawing is only limited by the size of the console
+    pub fn reset_clip(&mut self) {
+        self.clip = Rect(0, 0, u16::MAX, u16::MAX);
+    }
+
+    /// gets the region that drawing is limited to in console coordinates
+    pub fn clip(&self) -> Rect {
+        self.clip
+            .clip_to(&Rect(0, 0, self.console.size.0, self.console.size.1))
+    }
+
+    /// creates a new screen buffer of columns * rows cells and returns its handle
+    pub fn create_screen_buffer(&mut self, columns: u16, rows: u16) -> ScreenBuffer {
+        let mut console: Console = Console::new(columns, rows);
+        set_console_attribute(
+            &mut console,
+            Attribute(self.foreground_color | self.background_color),
+        );
+        let free_slot = (0..self.screen_buffers.len())
+            .find(|&index| index != self.target_buffer && self.screen_buffers[index].is_none());
+        match free_slot {
+            Some(index) => {
+                self.screen_buffers[index] = Some(console);
+                ScreenBuffer(index)
+            }
+            None => {
+                self.screen_buffers.push(Some(console));
+                ScreenBuffer(self.screen_buffers.len() - 1)
+            }
         }
-        if color & 2 == 2 {
-            value |= BACKGROUND_GREEN;
+    }
+
+    /// releases a screen buffer, the active and target buffers cannot be closed
+    pub fn close_screen_buffer(&mut self, buffer: ScreenBuffer) -> bool {
+        if buffer.0 == self.target_buffer || buffer.0 == self.active_buffer {
+            return false;
         }
-        if color & 4 == 4 {
-            value |= BACKGROUND_BLUE;
+        match self.screen_buffers.get_mut(buffer.0) {
+            Some(slot) if slot.is_some() => {
+                *slot = None;
+                true
+            }
+            _ => false,
         }
-        if color & 8 == 8 {
-            value |= BACKGROUND_INTENSITY;
+    }
+
+    /// gets the handle of the screen buffer that drawing calls write to
+    pub fn target_screen_buffer(&self) -> ScreenBuffer {
+        ScreenBuffer(self.target_buffer)
+    }
+
+    /// redirects all drawing calls to another screen buffer
+    pub fn set_target_screen_buffer(&mut self, buffer: ScreenBuffer) -> bool {
+        if buffer.0 == self.target_buffer {
+            return true;
         }
-        self.background_color = value;
+        let console = match self.screen_buffers.get_mut(buffer.0).and_then(Option::take) {
+            Some(console) => console,
+            None => return false,
+        };
+        let previous = std::mem::replace(&mut self.console, console);
+        self.screen_buffers[self.target_buffer] = Some(previous);
+        self.target_buffer = buffer.0;
         set_console_attribute(
             &mut self.console,
             Attribute(self.foreground_color | self.background_color),
         );
+        true
     }
 
-    pub fn set_fgcolor(&mut self, color: u16) {
-        let mut value: u16 = 0;
-        if color & 1 == 1 {
-            value |= FOREGROUND_RED;
+    /// gets the handle of the screen buffer that is presented
+    pub fn active_screen_buffer(&self) -> ScreenBuffer {
+        ScreenBuffer(self.active_buffer)
+    }
+
+    /// chooses which screen buffer is presented when iterating over the [Rusted] instance
+    pub fn set_active_screen_buffer(&mut self, buffer: ScreenBuffer) -> bool {
+        if self.screen_buffer(buffer).is_none() {
+            return false;
         }
-        if color & 2 == 2 {
-            value |= FOREGROUND_GREEN;
+        self.active_buffer = buffer.0;
+        self.history_offset = 0;
+        self.util_mark_presentation_dirty();
+        true
+    }
+
+    /// gets the console of the screen buffer that is presented
+    pub fn active_console(&self) -> &Console {
+        self.screen_buffer(ScreenBuffer(self.active_buffer))
+            .expect("active screen buffer is always open")
+    }
+
+    /// gets the console of any open screen buffer
+    pub fn screen_buffer(&self, buffer: ScreenBuffer) -> Option<&Console> {
+        if buffer.0 == self.target_buffer {
+            return Some(&self.console);
         }
-        if color & 4 == 4 {
-            value |= FOREGROUND_BLUE;
+        self.screen_buffers.get(buffer.0).and_then(Option::as_ref)
+    }
+
+    /// gets the console of any open screen buffer for modification
+    pub fn screen_buffer_mut(&mut self, buffer: ScreenBuffer) -> Option<&mut Console> {
+        if buffer.0 == self.target_buffer {
+            return Some(&mut self.console);
         }
-        if color & 8 == 8 {
-            value |= FOREGROUND_INTENSITY;
+        self.screen_buffers
+            .get_mut(buffer.0)
+            .and_then(Option::as_mut)
+    }
+
+    /// copies the src region of one screen buffer to the dst position of another
+    pub fn copy_screen_buffer_region(
+        &mut self,
+        from: ScreenBuffer,
+        src: Rect,
+        to: ScreenBuffer,
+        dst: Coord,
+    ) -> bool {
+        if from == to {
+            return match self.screen_buffer_mut(to) {
+                Some(console) => {
+                    copy_console_output(console, src, dst);
+                    true
+                }
+                None => false,
+            };
+        }
+        let size: Coord = Coord(src.width(), src.height());
+        let mut region: Vec<CharInfo> =
+            vec![CharInfo::default(); (size.0 as usize) * (size.1 as usize)];
+        match self.screen_buffer(from) {
+            Some(console) => read_console_output(console, &mut region, size, Coord(0, 0), src),
+            None => return false,
         }
-        self.foreground_color = value;
+        match self.screen_buffer_mut(to) {
+            Some(console) => {
+                write_console_output(console, &region, size, dst, Rect(0, 0, size.0, size.1))
+            }
+            None => return false,
+        }
+        true
+    }
+
+    /// switches the target buffer to 80x25, keeping the top-left contents
+    pub fn screen80x25(&mut self) {
+        self.resize(80, 25, ResizeMode::Crop);
+    }
+
+    /// switches the target buffer to 80x50, keeping the top-left contents
+    pub fn screen80x50(&mut self) {
+        self.resize(80, 50, ResizeMode::Crop);
+    }
+
+    /// resizes the target buffer, for example in reaction to a terminal resize
+    pub fn resize(&mut self, columns: u16, rows: u16, mode: ResizeMode) {
+        resize_console_buffer(&mut self.console, Coord(columns, rows), mode);
+        if self.follow_cursor {
+            ensure_console_cursor_visible(&mut self.console);
+        }
+    }
+
+    /// shows the w * h region of the target buffer at x, y
+    pub fn set_viewport(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        let (x, y) = (x.max(0) as u16, y.max(0) as u16);
+        let (w, h) = (w.max(0) as u16, h.max(0) as u16);
+        set_console_window_info(
+            &mut self.console,
+            Rect(x, y, x.saturating_add(w), y.saturating_add(h)),
+        );
+    }
+
+    /// pans the viewport of the target buffer by dx columns and dy rows
+    pub fn scroll_viewport(&mut self, dx: i32, dy: i32) {
+        scroll_console_window(&mut self.console, dx, dy);
+    }
+
+    /// pans the viewport of the target buffer by whole viewport pages
+    pub fn scroll_viewport_pages(&mut self, pages_x: i32, pages_y: i32) {
+        let viewport: Rect = self.console.viewport;
+        scroll_console_window(
+            &mut self.console,
+            pages_x * viewport.width() as i32,
+            pages_y * viewport.height() as i32,
+        );
+    }
+
+    /// when enabled, the viewport pans after every write to keep the cursor visible
+    pub fn set_follow_cursor(&mut self, follow_cursor: bool) {
+        self.follow_cursor = follow_cursor;
+        if follow_cursor {
+            ensure_console_cursor_visible(&mut self.console);
+        }
+    }
+
+    pub fn set_bgcolor(&mut self, color: u16) {
+        self.background_color = util_background_bits(color);
         set_console_attribute(
             &mut self.console,
             Attribute(self.foreground_color | self.background_color),
         );
     }
 
-    pub fn cls(&mut self) {
-        let count: u16 = self.console.size.0 * self.console.size.1;
-        fill_console_output_attribute(
+    pub fn set_fgcolor(&mut self, color: u16) {
+        self.foreground_color = util_foreground_bits(color);
+        set_console_attribute(
             &mut self.console,
-            count,
             Attribute(self.foreground_color | self.background_color),
-            Coord(0, 0),
         );
-        fill_console_output_character(&mut self.console, count, ' ', Coord(0, 0));
-        set_console_cursor_position(&mut self.console, Coord(0, 0));
     }
 
+    /// writes text at the cursor like a teletype, wrapping at the right edge and scrolling the
+    /// buffer into the scrollback when the bottom is reached
+    pub fn print(&mut self, text: &str) {
+        let Coord(width, height) = self.console.size;
+        if width == 0 || height == 0 {
+            return;
+        }
+        for character in text.chars() {
+            let Coord(x, y) = self.console.cursor;
+            if character != '\n' {
+                let index: usize = (x as usize) + (y as usize) * (width as usize);
+                if let Some(cell) = self.console.buffer.get_mut(index) {
+                    *cell = CharInfo(character, self.console.attribute);
+                    self.console.mark_dirty(Rect(x, y, x + 1, y + 1));
+                }
+                if x + 1 < width {
+                    self.console.cursor.0 = x + 1;
+                    continue;
+                }
+            }
+            if let Some(wrapped) = self.console.wrapped.get_mut(y as usize) {
+                *wrapped = character != '\n';
+            }
+            self.console.cursor.0 = 0;
+            if self.console.cursor.1 + 1 < height {
+                self.console.cursor.1 += 1;
+            } else {
+                scroll_console_screen_buffer(&mut self.console, 1);
+            }
+        }
+        if self.follow_cursor {
+            ensure_console_cursor_visible(&mut self.console);
+        }
+    }
+
+    /// scrolls the target buffer up by lines rows, keeping the evicted rows in its scrollback
+    pub fn scroll_up(&mut self, lines: u16) {
+        scroll_console_screen_buffer(&mut self.console, lines);
+    }
+
+    /// shows the active buffer offset rows back into its scrollback, 0 shows the live buffer
+    pub fn view_history(&mut self, offset: usize) {
+        let console: &Console = self.active_console();
+        let max_offset: usize = console.scrollback.len() + console.viewport.1 as usize;
+        let offset: usize = offset.min(max_offset);
+        if offset != self.history_offset {
+            self.history_offset = offset;
+            self.util_mark_presentation_dirty();
+        }
+    }
+
+    /// gets how many rows back into the scrollback the active buffer is shown
+    pub fn history_offset(&self) -> usize {
+        self.history_offset
+    }
+
+    /// moves the history view one viewport page back
+    pub fn history_page_up(&mut self) {
+        let page: usize = self.active_console().viewport.height().max(1) as usize;
+        self.view_history(self.history_offset + page);
+    }
+
+    /// moves the history view one viewport page forward, towards the live buffer
+    pub fn history_page_down(&mut self) {
+        let page: usize = self.active_console().viewport.height().max(1) as usize;
+        self.view_history(self.history_offset.saturating_sub(page));
+    }
+
+    /// searches the scrollback and buffer of the active buffer and highlights every match
+    ///
+    /// lines are numbered from the oldest scrollback row, the buffer rows follow the scrollback.
+    /// an empty needle or malformed pattern finds nothing
+    pub fn search_history(&mut self, needle: &str, kind: SearchKind) -> Vec<HistoryMatch> {
+        let console: &Console = self.active_console();
+        let mut matches: Vec<HistoryMatch> = vec![];
+        if let Some(pattern) = SearchPattern::new(needle, kind) {
+            matches = console.scrollback.search(&pattern);
+            let width: usize = console.size.0.max(1) as usize;
+            for (row, cells) in console.buffer.chunks(width).enumerate() {
+                pattern.find_in_row(console.scrollback.len() + row, cells, &mut matches);
+            }
+        }
+        // kept relative to the evicted rows so they stay on their rows as the scrollback fills
+        let evicted: usize = console.scrollback.evicted();
+        self.highlights = matches
+            .iter()
+            .map(|found| HistoryMatch {
+                line: found.line + evicted,
+                ..*found
+            })
+            .collect();
+        self.util_mark_presentation_dirty();
+        matches
+    }
+
+    /// scrolls the history view so that the line of the match is at the top of the viewport
+    pub fn reveal_history_match(&mut self, found: &HistoryMatch) {
+        let console: &Console = self.active_console();
+        let first_visible: usize = console.scrollback.len() + console.viewport.1 as usize;
+        self.view_history(first_visible.saturating_sub(found.line));
+    }
+
+    /// removes the highlighting of the last search
+    pub fn clear_highlights(&mut self) {
+        if !self.highlights.is_empty() {
+            self.highlights.clear();
+            self.util_mark_presentation_dirty();
+        }
+    }
+
+    /// sets the colors used to highlight search matches
+    pub fn set_highlight_color(&mut self, fgc: u16, bgc: u16) {
+        self.highlight_attribute = Attribute(util_foreground_bits(fgc) | util_background_bits(bgc));
+        self.util_mark_presentation_dirty();
+    }
+
+    /// takes the regions of the active buffer that changed since the last call, in the viewport
+    /// relative coordinates used when iterating over the [Rusted] instance
+    pub fn take_dirty_rects(&mut self) -> Vec<Rect> {
+        let active: ScreenBuffer = self.active_screen_buffer();
+        // while viewing history the buffer rows are presented history_offset rows lower
+        let offset: i32 = self.history_offset.min(u16::MAX as usize) as i32;
+        let console: &mut Console = self
+            .screen_buffer_mut(active)
+            .expect("active screen buffer is always open");
+        let viewport: Rect = console.viewport;
+        let screen: Rect = Rect(0, 0, viewport.width(), viewport.height());
+        console
+            .take_dirty_rects()
+            .iter()
+            .filter_map(|rect| rect.intersection(&viewport))
+            .map(|rect| rect.translate(-(viewport.0 as i32), offset - viewport.1 as i32))
+            .filter_map(|rect| rect.intersection(&screen))
+            .collect()
+    }
+
+    /// the history view and highlights change what is presented without touching the buffer
+    fn util_mark_presentation_dirty(&mut self) {
+        let active: ScreenBuffer = self.active_screen_buffer();
+        if let Some(console) = self.screen_buffer_mut(active) {
+            console.mark_all_dirty();
+        }
+    }
+
+    /// clears the clip region with the current colors and moves the cursor to the origin
+    pub fn cls(&mut self) {
+        let state: DrawState = self.draw_state();
+        state.clear(&mut self.console);
+        self.set_xy(0, 0);
+    }
+
+    /// clears the clip region of the surface with the current colors
+    pub fn cls_on<S: Surface + ?Sized>(&self, surface: &mut S) {
+        self.draw_state().clear(surface);
+    }
+
+    /// moves the cursor, positions outside of the console are clamped to its edges
     pub fn set_xy(&mut self, x: i32, y: i32) {
-        set_console_cursor_position(&mut self.console, Coord(x as u16, y as u16));
+        let (x, y) = (self.origin.0 + x, self.origin.1 + y);
+        let Coord(width, height) = self.console.size;
+        let position: Coord = Coord(
+            x.clamp(0, (width as i32 - 1).max(0)) as u16,
+            y.clamp(0, (height as i32 - 1).max(0)) as u16,
+        );
+        set_console_cursor_position(&mut self.console, position);
+        if self.follow_cursor {
+            ensure_console_cursor_visible(&mut self.console);
+        }
     }
 
     pub fn outchar(&mut self, x: i32, y: i32, character: char) {
-        set_console_cursor_position(&mut self.console, Coord(x as u16, y as u16));
-        write_console(&mut self.console, character.to_string().as_str());
+        let state: DrawState = self.draw_state();
+        state.cell(&mut self.console, x, y, character);
+        self.util_place_cursor((state.origin.0 + x + 1, state.origin.1 + y));
+    }
+
+    pub fn outchar_on<S: Surface + ?Sized>(
+        &self,
+        surface: &mut S,
+        x: i32,
+        y: i32,
+        character: char,
+    ) {
+        self.draw_state().cell(surface, x, y, character);
     }
 
+    /// draws text at x, y with the current colors, a newline continues at x on the next row.
+    /// the parts of the text outside of the clip are discarded
     pub fn outchars(&mut self, x: i32, y: i32, text: &str) {
-        set_console_cursor_position(&mut self.console, Coord(x as u16, y as u16));
-        write_console(&mut self.console, text);
+        let state: DrawState = self.draw_state();
+        let end: (i32, i32) = state.text(&mut self.console, x, y, text);
+        self.util_place_cursor(end);
     }
 
-    pub fn open_window(
+    /// leaves the cursor at end, the position after drawn text, like write_console does
+    fn util_place_cursor(&mut self, end: (i32, i32)) {
+        let Coord(width, height) = self.console.size;
+        if end.0 >= 0 && end.1 >= 0 && end.0 <= width as i32 && end.1 < height as i32 {
+            let position: Coord = if end.0 == width as i32 {
+                Coord(0, (end.1 as u16 + 1).min(height - 1))
+            } else {
+                Coord(end.0 as u16, end.1 as u16)
+            };
+            set_console_cursor_position(&mut self.console, position);
+        }
+        if self.follow_cursor {
+            ensure_console_cursor_visible(&mut self.console);
+        }
+    }
+
+    /// same as [Rusted::outchars] but draws on any surface and leaves the cursor alone
+    pub fn outchars_on<S: Surface + ?Sized>(&self, surface: &mut S, x: i32, y: i32, text: &str) {
+        self.draw_state().text(surface, x, y, text);
+    }
+
+    /// saves the cells under the window described by options, then draws it. returns the saved
+    /// cells and the client area in console coordinates, clipped to the console
+    pub fn open_window(&mut self, options: &WindowOptions) -> (Window, Rect) {
+        self.util_set_window_colors(options);
+        let state: DrawState = self.draw_state();
+        state.window(&mut self.console, options)
+    }
+
+    /// same as [Rusted::open_window] but saves the cells under the window from and draws it on
+    /// any surface
+    pub fn open_window_on<S: Surface + ?Sized>(
+        &mut self,
+        surface: &mut S,
+        options: &WindowOptions,
+    ) -> (Window, Rect) {
+        self.util_set_window_colors(options);
+        self.draw_state().window(surface, options)
+    }
+
+    fn util_set_window_colors(&mut self, options: &WindowOptions) {
+        if let Some((fgc, bgc)) = options.colors {
+            self.set_bgcolor(bgc);
+            self.set_fgcolor(fgc);
+        }
+    }
+
+    pub fn close_window(&mut self, window: &Window) {
+        let (x, y) = window.position();
+        self.console
+            .blit(window, window.bounds(), Coord(x as u16, y as u16));
+    }
+
+    /// restores the cells saved under the window onto the surface it was opened on
+    pub fn close_window_on<S: Surface + ?Sized>(&self, surface: &mut S, window: &Window) {
+        let (x, y) = window.position();
+        surface.blit(window, window.bounds(), Coord(x as u16, y as u16));
+    }
+
+    pub fn draw_button(
         &mut self,
         rect: (i32, i32, i32, i32),
+        caption: &str,
         fgc: u16,
         bgc: u16,
-        with_frame: bool,
-    ) -> Window {
-        let (x, y, w, h) = rect;
-        let mut wnd: Window = Window::new(x, y, w, h);
+        frame: impl Into<FrameStyle>,
+    ) {
+        self.set_bgcolor(bgc);
+        self.set_fgcolor(fgc);
+        let state: DrawState = self.draw_state();
+        state.button(&mut self.console, rect, caption, frame.into());
+    }
+
+    pub fn draw_button_on<S: Surface + ?Sized>(
+        &mut self,
+        surface: &mut S,
+        rect: (i32, i32, i32, i32),
+        caption: &str,
+        fgc: u16,
+        bgc: u16,
+        frame: impl Into<FrameStyle>,
+    ) {
+        self.set_bgcolor(bgc);
+        self.set_fgcolor(fgc);
+        self.draw_state()
+            .button(surface, rect, caption, frame.into());
+    }
+
+    /// draws the lines of the canvas at the origin with the current colors, joining them with the
+    /// box-drawing glyphs already on the console
+    pub fn draw_lines(&mut self, canvas: &LineCanvas) {
+        let state: DrawState = self.draw_state();
+        state.lines(&mut self.console, canvas);
+    }
+
+    pub fn draw_lines_on<S: Surface + ?Sized>(&self, surface: &mut S, canvas: &LineCanvas) {
+        self.draw_state().lines(surface, canvas);
+    }
+
+    /// draws the border of the w * h region at x, y, joining it with the lines it crosses or
+    /// touches
+    pub fn draw_joined_frame(&mut self, rect: (i32, i32, i32, i32), weight: LineWeight) {
+        let state: DrawState = self.draw_state();
+        state.joined_frame(&mut self.console, rect, weight);
+    }
+
+    pub fn draw_joined_frame_on<S: Surface + ?Sized>(
+        &self,
+        surface: &mut S,
+        rect: (i32, i32, i32, i32),
+        weight: LineWeight,
+    ) {
+        self.draw_state().joined_frame(surface, rect, weight);
+    }
+
+    /// snapshot of the current clip, origin, colors and cursor
+    fn draw_state(&self) -> DrawState {
+        DrawState {
+            clip: self.clip,
+            origin: self.origin,
+            background_color: self.background_color,
+            foreground_color: self.foreground_color,
+            cursor: self.console.cursor,
+        }
+    }
+}
 
+/// drawing primitives shared by the console and any other surface
+impl DrawState {
+    fn attribute(&self) -> Attribute {
+        Attribute(self.foreground_color | self.background_color)
+    }
+
+    /// gets the clip in the coordinates of the surface
+    fn clip_for<S: Surface + ?Sized>(&self, surface: &S) -> Rect {
+        self.clip.clip_to(&surface.bounds())
+    }
+
+    /// converts the w * h region at x, y relative to the origin into surface coordinates, the
+    /// parts left of or above the surface are cut off
+    fn surface_rect(&self, x: i32, y: i32, w: i32, h: i32) -> Rect {
+        let (x, y) = (self.origin.0 + x, self.origin.1 + y);
+        let clamp = |value: i32| value.clamp(0, u16::MAX as i32) as u16;
+        Rect(
+            clamp(x),
+            clamp(y),
+            clamp(x.saturating_add(w.max(0))),
+            clamp(y.saturating_add(h.max(0))),
+        )
+    }
+
+    fn clear<S: Surface + ?Sized>(&self, surface: &mut S) {
+        let clip: Rect = self.clip_for(surface);
+        surface.fill_rect(clip, CharInfo(' ', self.attribute()));
+    }
+
+    /// draws one character at x, y when it lies inside the clip
+    fn cell<S: Surface + ?Sized>(&self, surface: &mut S, x: i32, y: i32, character: char) {
+        let clip: Rect = self.clip_for(surface);
+        let (column, row) = (self.origin.0 + x, self.origin.1 + y);
+        if column >= clip.0 as i32
+            && column < clip.2 as i32
+            && row >= clip.1 as i32
+            && row < clip.3 as i32
         {
-            let x: u16 = x as u16;
-            let y: u16 = y as u16;
-            let w: u16 = w as u16;
-            let h: u16 = h as u16;
-
-            read_console_output(
-                &self.console,
-                &mut wnd.buffer,
-                Coord(w, h),
-                Coord(0, 0),
-                Rect::from_xywh(x, y, w, h),
+            surface.set_cell(
+                Coord(column as u16, row as u16),
+                CharInfo(character, self.attribute()),
             );
-            // println!("opening window buffer: {:?}", wnd.buffer);
         }
+    }
 
-        self.set_bgcolor(bgc);
-        self.set_fgcolor(fgc);
+    /// draws the text clipped and returns the position after its last character
+    fn text<S: Surface + ?Sized>(&self, surface: &mut S, x: i32, y: i32, text: &str) -> (i32, i32) {
+        let clip: Rect = self.clip_for(surface);
+        let attribute: Attribute = self.attribute();
+        let (left, mut top) = (self.origin.0 + x, self.origin.1 + y);
+        let mut end: (i32, i32) = (left, top);
+        for line in text.split('\n') {
+            let mut column: i32 = left;
+            let visible_row: bool = top >= clip.1 as i32 && top < clip.3 as i32;
+            for character in line.chars() {
+                if visible_row && column >= clip.0 as i32 && column < clip.2 as i32 {
+                    surface.set_cell(
+                        Coord(column as u16, top as u16),
+                        CharInfo(character, attribute),
+                    );
+                }
+                column += 1;
+            }
+            end = (column, top);
+            top += 1;
+        }
+        end
+    }
 
-        self.draw_window_fill(x, y, w, h);
+    /// fills the w * h region at x, y with the cell
+    fn fill<S: Surface + ?Sized>(
+        &self,
+        surface: &mut S,
+        rect: (i32, i32, i32, i32),
+        cell: CharInfo,
+    ) {
+        let (x, y, w, h) = rect;
+        let region: Rect = self
+            .surface_rect(x, y, w, h)
+            .clip_to(&self.clip_for(surface));
+        surface.fill_rect(region, cell);
+    }
 
-        if with_frame {
-            self.draw_window_frame(x, y, w, h);
+    /// fills the inside of the frame with the fill cell of the style, or with spaces when there
+    /// is no frame
+    fn window_fill<S: Surface + ?Sized>(
+        &self,
+        surface: &mut S,
+        rect: (i32, i32, i32, i32),
+        frame: FrameStyle,
+    ) {
+        // draw background fill, frameless windows fill up to their edge like their client rect
+        let (x, y, w, h) = rect;
+        match frame.nine_patch(self.attribute()) {
+            Some(patch) => self.fill(surface, (x + 1, y + 1, w - 2, h - 2), patch.fill()),
+            None => self.fill(surface, rect, CharInfo(' ', self.attribute())),
         }
-        wnd
     }
 
-    pub fn close_window(&mut self, window: &Window) {
-        let x: u16 = window.data[0] as u16;
-        let y: u16 = window.data[1] as u16;
-        let w: u16 = window.data[2] as u16;
-        let h: u16 = window.data[3] as u16;
-        // println!(
-        //     "closing window x {} y {} w {} h {}",
-        //     x, y, w, h,
-        // );
-        // println!("closing window buffer {:?}", window.buffer);
+    fn window_frame<S: Surface + ?Sized>(
+        &self,
+        surface: &mut S,
+        rect: (i32, i32, i32, i32),
+        frame: FrameStyle,
+    ) {
+        let patch: NinePatch = match frame.nine_patch(self.attribute()) {
+            Some(patch) => patch,
+            None => return,
+        };
+        let (x, y, w, h) = rect;
+        // draw top and bottom edge
+        self.fill(surface, (x, y, w, 1), patch.top());
+        self.fill(surface, (x, y + h - 1, w, 1), patch.bottom());
 
-        write_console_output(
-            &mut self.console,
-            &window.buffer,
-            Coord(w, h),
-            Coord(x, y),
-            Rect::from_xywh(0, 0, w, h),
-        )
+        // draw left and right edge
+        self.fill(surface, (x, y, 1, h), patch.left());
+        self.fill(surface, (x + w - 1, y, 1, h), patch.right());
+
+        // draw corners
+        self.fill(surface, (x, y, 1, 1), patch.top_left());
+        self.fill(surface, (x + w - 1, y, 1, 1), patch.top_right());
+        self.fill(surface, (x, y + h - 1, 1, 1), patch.bottom_left());
+        self.fill(surface, (x + w - 1, y + h - 1, 1, 1), patch.bottom_right());
     }
 
-    pub fn draw_button(&mut self, rect: (i32, i32, i32, i32), caption: &str, fgc: u16, bgc: u16) {
+    /// sets the attribute of the w * h region at x, y, keeping the characters
+    fn recolor<S: Surface + ?Sized>(
+        &self,
+        surface: &mut S,
+        rect: (i32, i32, i32, i32),
+        attribute: Attribute,
+    ) {
         let (x, y, w, h) = rect;
-        self.set_bgcolor(bgc);
-        self.set_fgcolor(fgc);
-        self.draw_window_fill(x, y, w, h);
-        self.draw_window_frame(x, y, w, h);
-        self.outchars(x + 2, y + 1, caption);
+        let region: Rect = self
+            .surface_rect(x, y, w, h)
+            .clip_to(&self.clip_for(surface));
+        surface.blend_rect(region, CharInfo(' ', attribute), BlitMode::AttributeOnly);
     }
 
-    fn draw_window_fill(&mut self, x: i32, y: i32, w: i32, h: i32) {
-        // draw background fill
-        for cy in 1..h - 1 {
-            let cy: i32 = y + cy;
-            for cx in 1..w - 1 {
-                self.outchar(x + cx, cy, ' ');
+    /// draws the glyphs of the canvas merged with the box-drawing glyphs underneath
+    fn lines<S: Surface + ?Sized>(&self, surface: &mut S, canvas: &LineCanvas) {
+        let clip: Rect = self.clip_for(surface);
+        for (coord, glyph) in canvas.glyphs() {
+            let (x, y) = (
+                self.origin.0 + coord.0 as i32,
+                self.origin.1 + coord.1 as i32,
+            );
+            if x < clip.0 as i32 || x >= clip.2 as i32 || y < clip.1 as i32 || y >= clip.3 as i32 {
+                continue;
             }
+            let at: Coord = Coord(x as u16, y as u16);
+            let under: Option<LineArms> = surface
+                .get_cell(at)
+                .and_then(|cell| LineArms::from_glyph(cell.0));
+            let glyph: char = match under {
+                Some(under) => canvas.arms(coord).merge(under).glyph().unwrap_or(glyph),
+                None => glyph,
+            };
+            surface.set_cell(at, CharInfo(glyph, self.attribute()));
         }
     }
 
-    fn draw_window_frame(&mut self, x: i32, y: i32, w: i32, h: i32) {
-        // draw top and bottom edge
-        for cx in 0..w {
-            self.outchar(x + cx, y, BOX_HORIZONTAL_DOUBLE);
-            self.outchar(x + cx, y + h - 1, BOX_HORIZONTAL_DOUBLE);
+    fn joined_frame<S: Surface + ?Sized>(
+        &self,
+        surface: &mut S,
+        rect: (i32, i32, i32, i32),
+        weight: LineWeight,
+    ) {
+        let (x, y, w, h) = rect;
+        // the canvas starts at the visible top-left of the frame in surface coordinates
+        let (x, y) = (self.origin.0 + x, self.origin.1 + y);
+        let (left, top) = (x.max(0), y.max(0));
+        let size: Size = Size(
+            (x + w - left).clamp(0, u16::MAX as i32) as u16,
+            (y + h - top).clamp(0, u16::MAX as i32) as u16,
+        );
+        let mut canvas: LineCanvas = LineCanvas::new(size);
+        canvas.frame((x - left, y - top, w, h), weight);
+        let state: DrawState = DrawState {
+            origin: (left, top),
+            ..*self
+        };
+        state.lines(surface, &canvas);
+    }
+
+    /// darkens the cells two columns to the right of and one row below the w * h region at x, y
+    fn shadow<S: Surface + ?Sized>(&self, surface: &mut S, rect: (i32, i32, i32, i32)) {
+        let (x, y, w, h) = rect;
+        let clip: Rect = self.clip_for(surface);
+        surface.darken_rect(self.surface_rect(x + w, y + 1, 2, h).clip_to(&clip));
+        surface.darken_rect(self.surface_rect(x + 2, y + h, w - 2, 1).clip_to(&clip));
+    }
+
+    /// saves the cells under the window and its optional shadow, then draws its fill, frame,
+    /// decorations and optional shadow. returns the saved cells and the clipped client area
+    fn window<S: Surface + ?Sized>(
+        &self,
+        surface: &mut S,
+        options: &WindowOptions,
+    ) -> (Window, Rect) {
+        let (rect, frame, with_shadow) = (options.rect, options.frame, options.shadow);
+        let (x, y, w, h) = rect;
+        let (shadow_w, shadow_h) = if with_shadow { (2, 1) } else { (0, 0) };
+        let saved: Rect = self
+            .surface_rect(x, y, w + shadow_w, h + shadow_h)
+            .clip_to(&surface.bounds());
+        let Size(saved_w, saved_h) = saved.size();
+        let mut wnd: Window = Window::new(
+            saved.0 as i32,
+            saved.1 as i32,
+            saved_w as i32,
+            saved_h as i32,
+        );
+        for row in saved.1..saved.3 {
+            let local_row: u16 = row - saved.1;
+            let cells: Option<&[CharInfo]> = surface
+                .row_cells(row)
+                .and_then(|cells| cells.get(saved.0 as usize..saved.2 as usize));
+            match (cells, wnd.row_cells_mut(local_row, 0, saved_w)) {
+                (Some(cells), Some(slots)) => slots.copy_from_slice(cells),
+                _ => {
+                    for column in saved.0..saved.2 {
+                        if let Some(cell) = surface.get_cell(Coord(column, row)) {
+                            wnd.set_cell(Coord(column - saved.0, local_row), cell);
+                        }
+                    }
+                }
+            }
         }
 
-        // draw left and right edge
-        for cy in 0..h {
-            self.outchar(x, y + cy, BOX_VERTICAL_DOUBLE);
-            self.outchar(x + w - 1, y + cy, BOX_VERTICAL_DOUBLE);
+        self.window_fill(surface, rect, frame);
+        self.window_frame(surface, rect, frame);
+        if let Some(title) = &options.title {
+            self.edge_text(
+                surface,
+                (x, y, w),
+                title,
+                options.title_align,
+                options.close_glyph,
+            );
+        }
+        if let Some(footer) = &options.footer {
+            self.edge_text(
+                surface,
+                (x, y + h - 1, w),
+                footer,
+                options.footer_align,
+                None,
+            );
+        }
+        if let (Some(glyph), Some((close_x, close_y, _, _))) =
+            (options.close_glyph, options.close_rect())
+        {
+            let mut encoded: [u8; 4] = [0; 4];
+            let marker: String = format!("[{}]", glyph.encode_utf8(&mut encoded));
+            self.text(surface, close_x, close_y, &marker);
+        }
+        if with_shadow {
+            self.shadow(surface, rect);
         }
+        let (cx, cy, cw, ch) = options.client_rect();
+        let client: Rect = self
+            .surface_rect(cx, cy, cw, ch)
+            .clip_to(&self.clip_for(surface));
+        (wnd, client)
+    }
 
-        // draw corners
-        self.outchar(x, y, BOX_TOPLEFT_DOUBLE);
-        self.outchar(x + w - 1, y, BOX_TOPRIGHT_DOUBLE);
-        self.outchar(x, y + h - 1, BOX_BOTLEFT_DOUBLE);
-        self.outchar(x + w - 1, y + h - 1, BOX_BOTRIGHT_DOUBLE);
+    /// draws text padded with a space on each side into the edge row at x, y that is w columns
+    /// wide, leaving the corners and the close marker free. the text is cut off when too long
+    fn edge_text<S: Surface + ?Sized>(
+        &self,
+        surface: &mut S,
+        edge: (i32, i32, i32),
+        text: &str,
+        align: TextAlign,
+        close_glyph: Option<char>,
+    ) {
+        let (x, y, w) = edge;
+        let reserved: i32 = if close_glyph.is_some() { 4 } else { 0 };
+        let available: i32 = w - 4 - reserved;
+        if available <= 0 || text.is_empty() {
+            return;
+        }
+        let padded: String = format!(" {} ", text);
+        let shown: String = padded.chars().take(available as usize).collect();
+        let length: i32 = shown.chars().count() as i32;
+        self.text(surface, x + 2 + align.offset(length, available), y, &shown);
+    }
+
+    fn button<S: Surface + ?Sized>(
+        &self,
+        surface: &mut S,
+        rect: (i32, i32, i32, i32),
+        caption: &str,
+        frame: FrameStyle,
+    ) {
+        let (x, y, _, _) = rect;
+        self.window_fill(surface, rect, frame);
+        self.window_frame(surface, rect, frame);
+        self.text(surface, x + 2, y + 1, caption);
+    }
+}
+
+fn util_foreground_bits(color: u16) -> u16 {
+    let mut value: u16 = 0;
+    if color & 1 == 1 {
+        value |= FOREGROUND_RED;
     }
+    if color & 2 == 2 {
+        value |= FOREGROUND_GREEN;
+    }
+    if color & 4 == 4 {
+        value |= FOREGROUND_BLUE;
+    }
+    if color & 8 == 8 {
+        value |= FOREGROUND_INTENSITY;
+    }
+    value
+}
+
+fn util_background_bits(color: u16) -> u16 {
+    util_foreground_bits(color) << 4
 }
 
 impl Default for Rusted {
@@ -197,18 +1009,30 @@ impl<'a> IntoIterator for &'a Rusted {
     type Item = (Coord, char, (u16, u16));
     type IntoIter = RustedIter<'a>;
     fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
+        let console: &Console = self.active_console();
         RustedIter {
-            size: self.console.size,
-            char_info: &self.console.buffer,
+            size: console.size,
+            viewport: console.viewport,
+            char_info: &console.buffer,
+            scrollback: &console.scrollback,
+            history_offset: self.history_offset,
+            highlights: &self.highlights,
+            highlight_attribute: self.highlight_attribute,
             index: 0,
         }
     }
 }
 
+/// iterates over the cells inside the viewport, coordinates are relative to the viewport
 #[doc(hidden)]
 pub struct RustedIter<'a> {
     size: Coord,
+    viewport: Rect,
     char_info: &'a [CharInfo],
+    scrollback: &'a Scrollback,
+    history_offset: usize,
+    highlights: &'a [HistoryMatch],
+    highlight_attribute: Attribute,
     index: usize,
 }
 
@@ -216,9 +1040,43 @@ impl<'a> Iterator for RustedIter<'a> {
     type Item = (Coord, char, (u16, u16));
 
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
-        if let Some((current, rest)) = self.char_info.split_first() {
-            let column = (self.index as u16) % self.size.0;
-            let row = (self.index as u16) / self.size.0;
+        let (width, height) = (
+            self.viewport.width() as usize,
+            self.viewport.height() as usize,
+        );
+        let current: Option<CharInfo> = if self.index < width * height {
+            // lines of the scrollback come first, followed by the rows of the buffer
+            let column: usize = self.viewport.0 as usize + self.index % width;
+            let line: usize =
+                (self.scrollback.len() + self.viewport.1 as usize + self.index / width)
+                    .saturating_sub(self.history_offset);
+            let cell: Option<CharInfo> = if line < self.scrollback.len() {
+                self.scrollback
+                    .line(line)
+                    .map(|row| row.get(column).copied().unwrap_or_default())
+            } else {
+                let row: usize = line - self.scrollback.len();
+                self.char_info
+                    .get(column + row * (self.size.0 as usize))
+                    .copied()
+            };
+            cell.map(|CharInfo(character, attribute)| {
+                let highlighted: bool = self
+                    .highlights
+                    .iter()
+                    .any(|found| found.contains(self.scrollback.evicted() + line, column as u16));
+                if highlighted {
+                    CharInfo(character, self.highlight_attribute)
+                } else {
+                    CharInfo(character, attribute)
+                }
+            })
+        } else {
+            None
+        };
+        if let Some(current) = current {
+            let column = (self.index % width) as u16;
+            let row = (self.index / width) as u16;
             let character = current.0;
             let coord = Coord(column, row);
             let mut background_color = 0;
@@ -249,7 +1107,6 @@ impl<'a> Iterator for RustedIter<'a> {
             }
             let result = (coord, character, (background_color, foreground_color));
             self.index += 1;
-            self.char_info = rest;
             Some(result)
         } else {
             self.index = 0;
@@ -337,3 +1194,537 @@ mod rd_console {
         // );
     }
 }
+
+#[cfg(test)]
+mod screen_buffers {
+    use crate::*;
+    #[test]
+    fn draws_to_target_and_presents_active() {
+        let mut rusted = Rusted::new();
+        set_console_buffer_size(&mut rusted.console, Coord(5, 2));
+        let front = rusted.target_screen_buffer();
+        let back = rusted.create_screen_buffer(5, 2);
+
+        rusted.outchars(0, 0, "front");
+        assert!(rusted.set_target_screen_buffer(back));
+        rusted.outchars(0, 0, "back");
+
+        let presented: String = (&rusted).into_iter().take(5).map(|cell| cell.1).collect();
+        assert_eq!(presented, "front");
+
+        assert!(rusted.set_active_screen_buffer(back));
+        let presented: String = (&rusted).into_iter().take(4).map(|cell| cell.1).collect();
+        assert_eq!(presented, "back");
+
+        assert!(!rusted.close_screen_buffer(back));
+        assert!(rusted.close_screen_buffer(front));
+        assert!(rusted.screen_buffer(front).is_none());
+    }
+
+    #[test]
+    fn copies_regions_between_buffers() {
+        let mut rusted = Rusted::new();
+        let front = rusted.target_screen_buffer();
+        let help = rusted.create_screen_buffer(10, 3);
+
+        rusted.set_target_screen_buffer(help);
+        rusted.outchars(0, 1, "F1 Help");
+        rusted.set_target_screen_buffer(front);
+
+        assert!(rusted.copy_screen_buffer_region(help, Rect(0, 1, 7, 2), front, Coord(2, 4)));
+        let row: String = rusted.console.buffer[(4 * 80 + 2)..(4 * 80 + 9)]
+            .iter()
+            .map(|cell| cell.0)
+            .collect();
+        assert_eq!(row, "F1 Help");
+    }
+}
+
+#[cfg(test)]
+mod viewport {
+    use crate::*;
+    #[test]
+    fn iterates_only_over_viewport() {
+        let mut rusted = Rusted::new();
+        set_console_buffer_size(&mut rusted.console, Coord(200, 500));
+        rusted.outchars(100, 300, "report");
+        rusted.set_viewport(98, 299, 80, 25);
+
+        let cells: Vec<(Coord, char, (u16, u16))> = (&rusted).into_iter().collect();
+        assert_eq!(cells.len(), 80 * 25);
+        assert_eq!(cells[80 + 2].0, Coord(2, 1));
+        assert_eq!(cells[80 + 2].1, 'r');
+    }
+
+    #[test]
+    fn follows_cursor_when_enabled() {
+        let mut rusted = Rusted::new();
+        set_console_buffer_size(&mut rusted.console, Coord(200, 500));
+        rusted.set_viewport(0, 0, 80, 25);
+        rusted.set_follow_cursor(true);
+
+        rusted.outchars(10, 100, "line");
+
+        assert_eq!(rusted.console.viewport, Rect(0, 76, 80, 101));
+    }
+
+    #[test]
+    fn leaves_the_cursor_after_a_single_character() {
+        let mut rusted = Rusted::new();
+        set_console_buffer_size(&mut rusted.console, Coord(200, 500));
+        rusted.set_viewport(0, 0, 80, 25);
+        rusted.set_xy(3, 3);
+
+        rusted.outchar(5, 5, 'x');
+        assert_eq!(rusted.console.cursor, Coord(6, 5));
+
+        rusted.set_follow_cursor(true);
+        rusted.outchar(10, 100, 'y');
+        assert_eq!(rusted.console.cursor, Coord(11, 100));
+        assert_eq!(rusted.console.viewport, Rect(0, 76, 80, 101));
+    }
+}
+
+#[cfg(test)]
+mod history {
+    use crate::*;
+    #[test]
+    fn pages_through_scrolled_lines() {
+        let mut rusted = Rusted::new();
+        set_console_buffer_size(&mut rusted.console, Coord(10, 3));
+        rusted.print("one\ntwo\nthree\nfour\nfive");
+
+        assert_eq!(rusted.console.scrollback.len(), 2);
+        let top: String = (&rusted).into_iter().take(4).map(|cell| cell.1).collect();
+        assert_eq!(top, "thre");
+
+        rusted.history_page_up();
+        assert_eq!(rusted.history_offset(), 2);
+        let top: String = (&rusted).into_iter().take(3).map(|cell| cell.1).collect();
+        assert_eq!(top, "one");
+
+        rusted.history_page_down();
+        assert_eq!(rusted.history_offset(), 0);
+    }
+
+    #[test]
+    fn highlights_search_matches() {
+        let mut rusted = Rusted::new();
+        set_console_buffer_size(&mut rusted.console, Coord(10, 2));
+        rusted.print("error 1\nok\nerror 22");
+
+        let matches = rusted.search_history(r"error \d+", SearchKind::Pattern);
+        assert_eq!(
+            matches,
+            vec![
+                HistoryMatch {
+                    line: 0,
+                    column: 0,
+                    length: 7
+                },
+                HistoryMatch {
+                    line: 2,
+                    column: 0,
+                    length: 8
+                },
+            ]
+        );
+
+        rusted.reveal_history_match(&matches[0]);
+        let cells: Vec<(Coord, char, (u16, u16))> = (&rusted).into_iter().collect();
+        assert_eq!(
+            cells[0].2,
+            (FOREGROUND_RED | FOREGROUND_GREEN | FOREGROUND_INTENSITY, 0)
+        );
+        assert_eq!(
+            cells[10].2,
+            (0, FOREGROUND_RED | FOREGROUND_GREEN | FOREGROUND_BLUE)
+        );
+    }
+
+    #[test]
+    fn keeps_highlights_on_their_rows_when_the_scrollback_evicts() {
+        let mut rusted = Rusted::new();
+        set_console_buffer_size(&mut rusted.console, Coord(10, 2));
+        rusted.console.scrollback.set_capacity(2);
+        rusted.print("a\nerror\nb\n");
+        rusted.search_history("error", SearchKind::Text);
+
+        rusted.print("c\n");
+        assert_eq!(rusted.console.scrollback.evicted(), 1);
+        rusted.view_history(2);
+        let highlighted: String = (&rusted)
+            .into_iter()
+            .filter(|cell| cell.2 == (FOREGROUND_RED | FOREGROUND_GREEN | FOREGROUND_INTENSITY, 0))
+            .map(|cell| cell.1)
+            .collect();
+        assert_eq!(highlighted, "error");
+    }
+}
+
+#[cfg(test)]
+mod clipping {
+    use crate::*;
+
+    fn row_text(rusted: &Rusted, row: usize) -> String {
+        let width: usize = rusted.console.size.0 as usize;
+        rusted.console.buffer[row * width..(row + 1) * width]
+            .iter()
+            .map(|cell| cell.0)
+            .collect()
+    }
+
+    #[test]
+    fn clips_negative_and_overflowing_coordinates() {
+        let mut rusted = Rusted::new();
+        set_console_buffer_size(&mut rusted.console, Coord(6, 2));
+        rusted.cls();
+
+        rusted.outchars(-2, 0, "abcdef");
+        rusted.outchars(4, 1, "xyz");
+        rusted.outchar(-1, 1, '!');
+
+        assert_eq!(row_text(&rusted, 0), "cdef  ");
+        assert_eq!(row_text(&rusted, 1), "    xy");
+    }
+
+    #[test]
+    fn restores_state_after_nested_drawing() {
+        let mut rusted = Rusted::new();
+        set_console_buffer_size(&mut rusted.console, Coord(8, 3));
+        rusted.cls();
+
+        rusted.push_state();
+        rusted.translate(2, 1);
+        rusted.set_clip(0, 0, 3, 1);
+        rusted.set_fgcolor(1);
+        rusted.outchars(-1, 0, "#local#");
+        rusted.outchars(0, 1, "hidden");
+        assert!(rusted.pop_state());
+        assert!(!rusted.pop_state());
+
+        rusted.outchars(0, 2, "global");
+
+        assert_eq!(row_text(&rusted, 1), "  loc   ");
+        assert_eq!(rusted.console.buffer[8 + 2].1, Attribute(FOREGROUND_RED));
+        assert_eq!(row_text(&rusted, 2), "global  ");
+        assert_eq!(rusted.origin(), (0, 0));
+        assert_eq!(rusted.clip(), Rect(0, 0, 8, 3));
+    }
+
+    #[test]
+    fn saves_partially_offscreen_windows() {
+        let mut rusted = Rusted::new();
+        set_console_buffer_size(&mut rusted.console, Coord(6, 4));
+        rusted.cls();
+        rusted.outchars(0, 0, "abcdef");
+
+        let (window, _) = rusted.open_window(&WindowOptions::new((-2, -1, 5, 3)).colors(7, 1));
+        assert_eq!(window.position(), (0, 0));
+        assert_eq!(window.buffer_size(), (3, 2));
+        assert_eq!(row_text(&rusted, 0), "  \u{2551}def");
+        assert_eq!(row_text(&rusted, 1), "\u{2550}\u{2550}\u{255D}   ");
+
+        rusted.close_window(&window);
+        assert_eq!(row_text(&rusted, 0), "abcdef");
+    }
+}
+
+#[cfg(test)]
+mod surfaces {
+    use crate::*;
+    #[test]
+    fn draws_on_offscreen_surfaces() {
+        let mut rusted = Rusted::new();
+        let mut offscreen: Console = Console::new(10, 4);
+        let mut window: Window = Window::new(0, 0, 6, 1);
+
+        let (saved, _) = rusted.open_window_on(
+            &mut offscreen,
+            &WindowOptions::new((1, 0, 4, 3)).colors(7, 1),
+        );
+        rusted.outchars_on(&mut window, 1, 0, "text");
+        {
+            let mut region: SubSurface = offscreen.sub_surface(Rect(2, 1, 4, 2));
+            rusted.outchars_on(&mut region, 0, 0, "ab!");
+        }
+
+        assert_eq!(
+            offscreen.get_cell(Coord(1, 0)).unwrap().0,
+            BOX_TOPLEFT_DOUBLE
+        );
+        assert_eq!(offscreen.get_cell(Coord(2, 1)).unwrap().0, 'a');
+        assert_eq!(offscreen.get_cell(Coord(3, 1)).unwrap().0, 'b');
+        assert_eq!(
+            offscreen.get_cell(Coord(4, 1)).unwrap().0,
+            BOX_VERTICAL_DOUBLE
+        );
+        assert_eq!(window.get_cell(Coord(3, 0)).unwrap().0, 'x');
+        assert!(rusted.console.buffer.iter().all(|cell| cell.0 == '\0'));
+
+        rusted.close_window_on(&mut offscreen, &saved);
+        assert!(offscreen.buffer.iter().all(|cell| cell.0 == '\0'));
+    }
+}
+
+#[cfg(test)]
+mod frame_styles {
+    use crate::test_util::row;
+    use crate::*;
+
+    #[test]
+    fn draws_windows_and_buttons_with_the_style() {
+        let mut rusted = Rusted::new();
+        rusted.open_window(
+            &WindowOptions::new((0, 0, 4, 3))
+                .colors(7, 0)
+                .frame(FrameStyle::Ascii),
+        );
+        assert_eq!(row(&rusted, 0, 4), "+--+");
+        assert_eq!(row(&rusted, 1, 4), "|  |");
+
+        rusted.draw_button((0, 3, 4, 3), "", 7, 0, FrameStyle::Rounded);
+        assert_eq!(row(&rusted, 3, 4), "\u{256D}\u{2500}\u{2500}\u{256E}");
+
+        let (window, _) =
+            rusted.open_window(&WindowOptions::new((0, 6, 4, 3)).colors(7, 0).frame(false));
+        assert_eq!(row(&rusted, 6, 4), "    ");
+        assert_eq!(row(&rusted, 7, 4), "    ");
+        rusted.close_window(&window);
+    }
+
+    #[test]
+    fn draws_custom_nine_patches_with_their_own_cells() {
+        let mut rusted = Rusted::new();
+        let mut patch: NinePatch = NinePatch::from_chars(
+            [['/', '~', '\\'], ['(', '.', ')'], ['\\', '_', '/']],
+            Attribute(2),
+        );
+        patch.0[1][1] = CharInfo(':', Attribute(5));
+
+        rusted.open_window(&WindowOptions::new((0, 0, 4, 3)).colors(7, 0).frame(patch));
+
+        assert_eq!(row(&rusted, 0, 4), "/~~\\");
+        assert_eq!(row(&rusted, 1, 4), "(::)");
+        assert_eq!(row(&rusted, 2, 4), "\\__/");
+        assert_eq!(
+            rusted.console.get_cell(Coord(0, 0)).unwrap().1,
+            Attribute(2)
+        );
+        assert_eq!(
+            rusted.console.get_cell(Coord(1, 1)).unwrap().1,
+            Attribute(5)
+        );
+    }
+}
+
+#[cfg(test)]
+mod joined_frames {
+    use crate::test_util::row;
+    use crate::*;
+
+    #[test]
+    fn joins_adjoining_and_crossing_frames() {
+        let mut rusted = Rusted::new();
+        rusted.draw_joined_frame((0, 0, 5, 3), LineWeight::Double);
+        rusted.draw_joined_frame((4, 0, 4, 3), LineWeight::Double);
+        rusted.draw_joined_frame((2, 1, 4, 3), LineWeight::Single);
+
+        assert_eq!(
+            row(&rusted, 0, 8),
+            "\u{2554}\u{2550}\u{2550}\u{2550}\u{2566}\u{2550}\u{2550}\u{2557}"
+        );
+        assert_eq!(
+            row(&rusted, 1, 8),
+            "\u{2551}\0\u{250C}\u{2500}\u{256B}\u{2510}\0\u{2551}"
+        );
+        assert_eq!(
+            row(&rusted, 2, 8),
+            "\u{255A}\u{2550}\u{256A}\u{2550}\u{2569}\u{256A}\u{2550}\u{255D}"
+        );
+    }
+
+    #[test]
+    fn draws_canvases_at_the_origin() {
+        let mut rusted = Rusted::new();
+        let mut canvas: LineCanvas = LineCanvas::new(Size(3, 1));
+        canvas.hline(0, 0, 3, LineWeight::Single);
+        rusted.translate(-1, 2);
+        rusted.draw_lines(&canvas);
+
+        assert_eq!(row(&rusted, 2, 3), "\u{2500}\u{2500}\0");
+    }
+}
+
+#[cfg(test)]
+mod decorations {
+    use crate::test_util::row;
+    use crate::*;
+
+    #[test]
+    fn draws_titles_footers_and_the_close_marker() {
+        let mut rusted = Rusted::new();
+        let options: WindowOptions = WindowOptions::new((0, 0, 16, 5))
+            .frame(FrameStyle::Ascii)
+            .title("Edit", TextAlign::Left)
+            .footer("F1 Help", TextAlign::Right)
+            .close_glyph('x')
+            .padding(1, 1);
+
+        let (_, client) = rusted.open_window(&options);
+
+        assert_eq!(row(&rusted, 0, 16), "+- Edit ---[x]-+");
+        assert_eq!(row(&rusted, 4, 16), "+---- F1 Help -+");
+        assert_eq!(client, Rect(2, 2, 14, 3));
+        assert_eq!(options.close_rect(), Some((11, 0, 3, 1)));
+    }
+
+    #[test]
+    fn centers_and_cuts_off_titles() {
+        let mut rusted = Rusted::new();
+        rusted.open_window(
+            &WindowOptions::new((0, 0, 10, 3))
+                .frame(FrameStyle::Ascii)
+                .title("Hi", TextAlign::Center),
+        );
+        rusted.open_window(
+            &WindowOptions::new((0, 3, 10, 3))
+                .frame(FrameStyle::Ascii)
+                .title("Confirmation", TextAlign::Center),
+        );
+
+        assert_eq!(row(&rusted, 0, 10), "+-- Hi --+");
+        assert_eq!(row(&rusted, 3, 10), "+- Confi-+");
+    }
+
+    #[test]
+    fn draws_dialogs_and_windows_with_the_theme() {
+        let mut rusted = Rusted::new();
+        rusted.set_theme(Theme {
+            window: (0, 3),
+            dialog: (14, 1),
+            frame: FrameStyle::Ascii,
+            ..Theme::default()
+        });
+        let mut message: RustedMessage = RustedMessage::new(false);
+        message.show(&mut rusted, vec!["saved"]);
+
+        assert_eq!(
+            rusted.console.get_cell(Coord(8, 10)),
+            Some(CharInfo('+', Rusted::color_attribute(14, 1)))
+        );
+        message.hide(&mut rusted);
+
+        let options: WindowOptions = rusted.theme().window_options((0, 0, 4, 3));
+        rusted.open_window(&options);
+        assert_eq!(
+            rusted.console.get_cell(Coord(0, 0)),
+            Some(CharInfo('+', Rusted::color_attribute(0, 3)))
+        );
+    }
+
+    #[test]
+    fn shows_dialog_titles() {
+        let mut rusted = Rusted::new();
+        let mut choice: RustedChoice = RustedChoice::new()
+            .with_title("Confirm")
+            .with_frame_style(FrameStyle::Ascii);
+        choice.show_yes_no(&mut rusted, "Quit?");
+
+        let top: String = (0..80)
+            .map(|x| rusted.console.get_cell(Coord(x, 9)).unwrap().0)
+            .collect();
+        assert!(top.contains("- Confirm -"));
+    }
+}
+
+#[cfg(test)]
+mod focus_indicator {
+    use crate::*;
+    #[test]
+    fn recolors_the_focused_element_with_the_theme() {
+        let mut rusted = Rusted::new();
+        rusted.set_theme(Theme {
+            focused: (15, 1),
+            ..Theme::default()
+        });
+        rusted.outchars(0, 0, "[ OK ]");
+
+        rusted.draw_focus_indicator((1, 0, 4, 1));
+
+        let focused: Attribute = Rusted::color_attribute(15, 1);
+        assert_eq!(
+            rusted.console.get_cell(Coord(2, 0)),
+            Some(CharInfo('O', focused))
+        );
+        assert_ne!(rusted.console.get_cell(Coord(0, 0)).unwrap().1, focused);
+        assert_ne!(rusted.console.get_cell(Coord(5, 0)).unwrap().1, focused);
+    }
+}
+
+#[cfg(test)]
+mod shadows {
+    use crate::*;
+    #[test]
+    fn darkens_and_restores_the_cells_under_the_shadow() {
+        let mut rusted = Rusted::new();
+        let under: Attribute = Attribute(FOREGROUND_INTENSITY | FOREGROUND_GREEN | BACKGROUND_BLUE);
+        rusted
+            .console
+            .fill_rect(Rect(0, 0, 80, 25), CharInfo('.', under));
+        let before: Vec<CharInfo> = rusted.console.buffer.clone();
+
+        let (window, _) =
+            rusted.open_window(&WindowOptions::new((2, 2, 6, 4)).colors(15, 1).shadow(true));
+
+        assert_eq!(window.position(), (2, 2));
+        assert_eq!(window.buffer_size(), (8, 5));
+        let shadowed = CharInfo('.', Attribute(FOREGROUND_GREEN));
+        assert_eq!(rusted.console.get_cell(Coord(8, 3)), Some(shadowed));
+        assert_eq!(rusted.console.get_cell(Coord(9, 6)), Some(shadowed));
+        assert_eq!(rusted.console.get_cell(Coord(4, 6)), Some(shadowed));
+        assert_eq!(
+            rusted.console.get_cell(Coord(8, 2)),
+            Some(CharInfo('.', under))
+        );
+        assert_eq!(
+            rusted.console.get_cell(Coord(3, 6)),
+            Some(CharInfo('.', under))
+        );
+
+        rusted.close_window(&window);
+        assert_eq!(rusted.console.buffer, before);
+    }
+}
+
+#[cfg(test)]
+mod dirty_rects {
+    use crate::*;
+    #[test]
+    fn reports_changes_relative_to_the_viewport() {
+        let mut rusted = Rusted::new();
+        set_console_buffer_size(&mut rusted.console, Coord(200, 100));
+        rusted.set_viewport(10, 10, 80, 25);
+        rusted.take_dirty_rects();
+
+        rusted.outchars(15, 12, "hello");
+        rusted.outchars(0, 0, "offscreen");
+
+        assert_eq!(rusted.take_dirty_rects(), vec![Rect(5, 2, 10, 3)]);
+        assert_eq!(rusted.take_dirty_rects(), vec![]);
+    }
+
+    #[test]
+    fn moves_changes_down_while_viewing_history() {
+        let mut rusted = Rusted::new();
+        set_console_buffer_size(&mut rusted.console, Coord(10, 3));
+        rusted.print("one\ntwo\nthree\nfour\nfive");
+        rusted.view_history(1);
+        rusted.take_dirty_rects();
+
+        rusted.outchars(0, 0, "x");
+        rusted.outchars(0, 2, "y");
+
+        assert_eq!(rusted.take_dirty_rects(), vec![Rect(0, 1, 1, 2)]);
+    }
+}
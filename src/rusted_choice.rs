@@ -1,10 +1,10 @@
 use crate::*;
 
 pub struct RustedChoice {
-    pub selected_choice: Option<u8>,
+    pub selected_choice: Option<usize>,
     is_open: bool,
     window: Option<Window>,
-    cursor: u8,
+    cursor: usize,
 
     question: String,
     options: Vec<String>,
@@ -12,6 +12,13 @@ pub struct RustedChoice {
     q_y: i32,
     opt_x: i32,
     opt_y: i32,
+    /// the index of the first option shown
+    scroll: usize,
+    /// the number of option rows that fit in the box
+    visible: usize,
+    /// the frame of the theme when None
+    frame_style: Option<FrameStyle>,
+    title: Option<String>,
 }
 
 impl RustedChoice {
@@ -27,20 +34,49 @@ impl RustedChoice {
             q_y: 0,
             opt_x: 0,
             opt_y: 0,
+            scroll: 0,
+            visible: 0,
+            frame_style: None,
+            title: None,
         }
     }
 
+    /// sets the frame drawn around the choice instead of the frame of the theme
+    pub fn with_frame_style(mut self, frame_style: FrameStyle) -> Self {
+        self.frame_style = Some(frame_style);
+        self
+    }
+
+    /// sets the text centered in the top edge of the choice, such as "Confirm"
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// sets the question and options shown when opened as a [Dialog]
+    pub fn with_choice(mut self, question: &str, options: Vec<String>) -> Self {
+        self.question = question.to_string();
+        self.options = options;
+        self
+    }
+
+    /// moves the cursor to the previous option, wrapping around to the last one
     pub fn move_cursor_prev(&mut self) {
-        if self.is_open {
-            self.cursor = (self.cursor - 1) % self.options.len() as u8;
+        if self.is_open && !self.options.is_empty() {
+            let count: usize = self.options.len();
+            self.cursor = (self.cursor + count - 1) % count;
             self.selected_choice = Some(self.cursor);
+            self.util_scroll_to_cursor();
         }
     }
 
+    /// moves the cursor to the next option, wrapping around to the first one
     pub fn move_cursor_next(&mut self) {
-        if self.is_open {
-            self.cursor = (self.cursor + 1) % self.options.len() as u8;
+        if self.is_open && !self.options.is_empty() {
+            let count: usize = self.options.len();
+            self.cursor = (self.cursor + 1) % count;
             self.selected_choice = Some(self.cursor);
+            self.util_scroll_to_cursor();
         }
     }
 
@@ -55,9 +91,10 @@ impl RustedChoice {
         let (console_width, console_height) =
             (ctx.console.size.0 as i32, ctx.console.size.1 as i32);
 
+        // options that do not fit on the console scroll within the box
         let (box_width, box_height) = (
             ((console_width as f32) * 0.8) as i32,
-            (options.len() + 4) as i32,
+            ((options.len() + 4) as i32).min(console_height).max(5),
         );
 
         let (box_x, box_y) = (
@@ -70,38 +107,49 @@ impl RustedChoice {
             box_y + 2,
         );
 
-        let (opt_x, opt_y) = (box_x + 2 + (box_width / 2) - 5, 2);
+        let (opt_x, opt_y) = (box_x + 2 + (box_width / 2) - 5, box_y + 3);
 
         self.q_x = q_x;
         self.q_y = q_y;
         self.opt_x = opt_x;
         self.opt_y = opt_y;
 
-        let fgc: u16 = 1 | 2 | 4 | 8;
-        let bgc: u16 = 4;
+        let (fgc, bgc) = ctx.theme().dialog;
+        let frame_style: FrameStyle = self.frame_style.unwrap_or(ctx.theme().frame);
 
-        self.window = Some(ctx.open_window((box_x, box_y, box_width, box_height), fgc, bgc, true));
+        let mut window_options: WindowOptions =
+            WindowOptions::new((box_x, box_y, box_width, box_height))
+                .colors(fgc, bgc)
+                .frame(frame_style);
+        if let Some(title) = &self.title {
+            window_options = window_options.title(title, TextAlign::Center);
+        }
+        let (window, _) = ctx.open_window(&window_options);
+        self.window = Some(window);
 
         self.question = question.to_string();
         self.options = options;
         self.cursor = 0;
         self.selected_choice = Some(0);
-
-        self.redraw(ctx);
+        self.scroll = 0;
+        self.visible = (box_height - 4) as usize;
 
         self.is_open = true;
+        self.redraw(ctx);
     }
 
     pub fn redraw(&self, ctx: &mut Rusted) {
         if self.is_open {
             ctx.outchars(self.q_x, self.q_y, self.question.as_str());
-            for (index, opt) in self.options.iter().enumerate() {
-                let y: i32 = (index as i32) + self.opt_y;
-                ctx.outchars(self.opt_x, y, opt.as_str());
+            let width: usize = self.options.iter().map(|opt| opt.len()).max().unwrap_or(0);
+            let shown = self.options.iter().skip(self.scroll).take(self.visible);
+            for (row, opt) in shown.enumerate() {
+                let y: i32 = (row as i32) + self.opt_y;
+                ctx.outchars(self.opt_x, y, &format!("{:<1$}", opt, width));
             }
             ctx.outchar(
                 self.opt_x,
-                self.opt_y + (self.cursor as i32),
+                self.opt_y + (self.cursor - self.scroll) as i32,
                 DOUBLE_RIGHT_ARROW,
             );
         }
@@ -109,10 +157,53 @@ impl RustedChoice {
 
     pub fn hide(&mut self, ctx: &mut Rusted) {
         if self.is_open {
-            if let Some(window) = &self.window {
-                ctx.close_window(window);
+            if let Some(window) = self.window.take() {
+                ctx.close_window(&window);
             }
+            self.is_open = false;
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    fn util_scroll_to_cursor(&mut self) {
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + self.visible {
+            self.scroll = self.cursor + 1 - self.visible;
+        }
+    }
+}
+
+/// Up and Down move the cursor, Enter chooses the option under it and Escape cancels
+impl Dialog for RustedChoice {
+    fn open(&mut self, ctx: &mut Rusted) {
+        let (question, options) = (self.question.clone(), self.options.clone());
+        self.show_choice(ctx, &question, options);
+    }
+
+    fn close(&mut self, ctx: &mut Rusted) {
+        self.hide(ctx);
+    }
+
+    fn handle_event(&mut self, ctx: &mut Rusted, event: &InputEvent) -> Option<DialogResult> {
+        let InputEvent::Key(key) = event else {
+            return None;
+        };
+        if !key.key_down {
+            return None;
         }
+        match key.virtual_key_code {
+            VK_UP => self.move_cursor_prev(),
+            VK_DOWN => self.move_cursor_next(),
+            VK_RETURN => return Some(DialogResult::Choice(self.cursor)),
+            VK_ESCAPE => return Some(DialogResult::Cancelled),
+            _ => return None,
+        }
+        self.redraw(ctx);
+        None
     }
 }
 
@@ -121,3 +212,62 @@ impl Default for RustedChoice {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod option_scrolling {
+    use crate::*;
+
+    #[test]
+    fn chooses_beyond_the_first_256_options() {
+        let mut rusted = Rusted::new();
+        let options: Vec<String> = (0..300).map(|index| format!("  {}", index)).collect();
+        let mut choice: RustedChoice = RustedChoice::new().with_choice("Pick one", options);
+        choice.open(&mut rusted);
+
+        for _ in 0..299 {
+            choice.move_cursor_next();
+        }
+        assert_eq!(choice.selected_choice, Some(299));
+        let enter: InputEvent = InputEvent::Key(KeyEvent::new(VK_RETURN, '\0'));
+        assert_eq!(
+            choice.handle_event(&mut rusted, &enter),
+            Some(DialogResult::Choice(299))
+        );
+        choice.move_cursor_next();
+        assert_eq!(choice.selected_choice, Some(0));
+    }
+
+    #[test]
+    fn scrolls_choices_taller_than_the_console() {
+        let mut rusted = Rusted::new();
+        let before: Vec<CharInfo> = rusted.console.buffer.clone();
+        let options: Vec<String> = (0..100)
+            .map(|index| format!("  Option {}", index))
+            .collect();
+        let mut choice: RustedChoice = RustedChoice::new();
+        choice.show_choice(&mut rusted, "Pick one", options);
+
+        let rows: Vec<String> = (0..25)
+            .map(|y| {
+                (0..80)
+                    .map(|x| rusted.console.get_cell(Coord(x, y)).unwrap().0)
+                    .collect()
+            })
+            .collect();
+        assert!(rows[3].contains("\u{00BB} Option 0 "));
+        assert!(rows[23].contains("  Option 20"));
+        assert!(!rows.iter().any(|row| row.contains("Option 21")));
+
+        for _ in 0..21 {
+            choice.move_cursor_next();
+        }
+        choice.redraw(&mut rusted);
+        let last: String = (0..80)
+            .map(|x| rusted.console.get_cell(Coord(x, 23)).unwrap().0)
+            .collect();
+        assert!(last.contains("\u{00BB} Option 21"));
+
+        choice.hide(&mut rusted);
+        assert_eq!(rusted.console.buffer, before);
+    }
+}
@@ -4,6 +4,10 @@ pub struct RustedMessage {
     is_open: bool,
     window: Option<Window>,
     center_align_text: bool,
+    /// the frame of the theme when None
+    frame_style: Option<FrameStyle>,
+    title: Option<String>,
+    lines: Vec<String>,
 }
 
 impl RustedMessage {
@@ -12,13 +16,35 @@ impl RustedMessage {
             is_open: false,
             window: None,
             center_align_text,
+            frame_style: None,
+            title: None,
+            lines: vec![],
         }
     }
 
+    /// sets the frame drawn around the message instead of the frame of the theme
+    pub fn with_frame_style(mut self, frame_style: FrameStyle) -> Self {
+        self.frame_style = Some(frame_style);
+        self
+    }
+
+    /// sets the text centered in the top edge of the message, such as "Confirm"
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// sets the lines shown when opened as a [Dialog]
+    pub fn with_lines(mut self, lines: Vec<&str>) -> Self {
+        self.lines = lines.into_iter().map(String::from).collect();
+        self
+    }
+
     pub fn show(&mut self, ctx: &mut Rusted, lines: Vec<&str>) {
         if self.is_open {
             return;
         }
+        self.lines = lines.iter().map(|&line| line.to_string()).collect();
         let (console_width, console_height) =
             (ctx.console.size.0 as i32, ctx.console.size.1 as i32);
 
@@ -32,10 +58,17 @@ impl RustedMessage {
             (console_height - box_height) / 2,
         );
 
-        let fgc: u16 = 1 | 2 | 4 | 8;
-        let bgc: u16 = 4;
+        let (fgc, bgc) = ctx.theme().dialog;
+        let frame_style: FrameStyle = self.frame_style.unwrap_or(ctx.theme().frame);
 
-        self.window = Some(ctx.open_window((box_x, box_y, box_width, box_height), fgc, bgc, true));
+        let mut options: WindowOptions = WindowOptions::new((box_x, box_y, box_width, box_height))
+            .colors(fgc, bgc)
+            .frame(frame_style);
+        if let Some(title) = &self.title {
+            options = options.title(title, TextAlign::Center);
+        }
+        let (window, _) = ctx.open_window(&options);
+        self.window = Some(window);
 
         if self.center_align_text {
             for (index, &line) in lines.iter().enumerate() {
@@ -55,9 +88,37 @@ impl RustedMessage {
 
     pub fn hide(&mut self, ctx: &mut Rusted) {
         if self.is_open {
-            if let Some(window) = &self.window {
-                ctx.close_window(window);
+            if let Some(window) = self.window.take() {
+                ctx.close_window(&window);
             }
+            self.is_open = false;
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+}
+
+/// Enter and Space acknowledge the message, Escape cancels it
+impl Dialog for RustedMessage {
+    fn open(&mut self, ctx: &mut Rusted) {
+        let lines: Vec<String> = self.lines.clone();
+        self.show(ctx, lines.iter().map(String::as_str).collect());
+    }
+
+    fn close(&mut self, ctx: &mut Rusted) {
+        self.hide(ctx);
+    }
+
+    fn handle_event(&mut self, _ctx: &mut Rusted, event: &InputEvent) -> Option<DialogResult> {
+        match event {
+            InputEvent::Key(key) if key.key_down => match key.virtual_key_code {
+                VK_RETURN | VK_SPACE => Some(DialogResult::Ok),
+                VK_ESCAPE => Some(DialogResult::Cancelled),
+                _ => None,
+            },
+            _ => None,
         }
     }
 }
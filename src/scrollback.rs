@@ -0,0 +1,374 @@
+use std::collections::VecDeque;
+
+use crate::console::CharInfo;
+
+/// number of rows kept by the scrollback of a new console
+pub const DEFAULT_SCROLLBACK_CAPACITY: usize = 1000;
+
+/// bounded ring of the rows that scrolled off the top of a console, oldest first
+#[derive(Debug, Clone)]
+pub struct Scrollback {
+    #[doc(hidden)]
+    lines: VecDeque<Vec<CharInfo>>,
+    #[doc(hidden)]
+    capacity: usize,
+    #[doc(hidden)]
+    evicted: usize,
+}
+
+impl Scrollback {
+    /// create an empty scrollback that keeps at most capacity rows
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            capacity,
+            evicted: 0,
+        }
+    }
+
+    /// gets the maximum number of rows kept
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// changes the maximum number of rows kept, dropping the oldest rows that no longer fit
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.lines.len() > capacity {
+            self.lines.pop_front();
+            self.evicted += 1;
+        }
+    }
+
+    /// gets the number of rows kept
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// stores a row, evicting the oldest row when the scrollback is full
+    pub fn push(&mut self, row: Vec<CharInfo>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+            self.evicted += 1;
+        }
+        self.lines.push_back(row);
+    }
+
+    /// gets a row by index, 0 is the oldest row
+    pub fn line(&self, index: usize) -> Option<&[CharInfo]> {
+        self.lines.get(index).map(Vec::as_slice)
+    }
+
+    pub fn clear(&mut self) {
+        self.evicted += self.lines.len();
+        self.lines.clear();
+    }
+
+    /// gets the number of rows dropped from the front so far. added to a line index it gives a
+    /// number that stays with the row while older rows are evicted
+    pub fn evicted(&self) -> usize {
+        self.evicted
+    }
+
+    /// finds every match of the pattern, lines are indexed like [Scrollback::line]
+    pub fn search(&self, pattern: &SearchPattern) -> Vec<HistoryMatch> {
+        let mut matches: Vec<HistoryMatch> = vec![];
+        for (line, row) in self.lines.iter().enumerate() {
+            pattern.find_in_row(line, row, &mut matches);
+        }
+        matches
+    }
+}
+
+impl Default for Scrollback {
+    fn default() -> Self {
+        Self::new(DEFAULT_SCROLLBACK_CAPACITY)
+    }
+}
+
+/// how the needle of a [SearchPattern] is interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchKind {
+    /// the needle is matched literally
+    Text,
+    /// the needle is a regex-like pattern supporting `. * + ? ^ $`, `[a-z]`, `[^...]`, `\d \w \s`
+    Pattern,
+}
+
+/// a run of matching cells, line 0 is the oldest row of the scrollback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryMatch {
+    pub line: usize,
+    pub column: u16,
+    pub length: u16,
+}
+
+impl HistoryMatch {
+    /// checks if the cell at line, column is part of the match
+    pub fn contains(&self, line: usize, column: u16) -> bool {
+        line == self.line && column >= self.column && column - self.column < self.length
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Atom {
+    Literal(char),
+    Any,
+    Digit,
+    Word,
+    Space,
+    Class(Vec<(char, char)>, bool),
+}
+
+impl Atom {
+    fn matches(&self, character: char) -> bool {
+        match self {
+            Atom::Literal(literal) => *literal == character,
+            Atom::Any => true,
+            Atom::Digit => character.is_ascii_digit(),
+            Atom::Word => character.is_alphanumeric() || character == '_',
+            Atom::Space => character.is_whitespace(),
+            Atom::Class(ranges, negated) => {
+                ranges
+                    .iter()
+                    .any(|&(low, high)| character >= low && character <= high)
+                    != *negated
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Repeat {
+    One,
+    ZeroOrOne,
+    ZeroOrMore,
+    OneOrMore,
+}
+
+/// compiled search needle used by the scrollback search
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchPattern {
+    #[doc(hidden)]
+    tokens: Vec<(Atom, Repeat)>,
+    #[doc(hidden)]
+    anchored_start: bool,
+    #[doc(hidden)]
+    anchored_end: bool,
+}
+
+impl SearchPattern {
+    /// compiles the needle, returns None for an empty needle or a malformed pattern
+    pub fn new(needle: &str, kind: SearchKind) -> Option<Self> {
+        if needle.is_empty() {
+            return None;
+        }
+        match kind {
+            SearchKind::Text => Some(Self {
+                tokens: needle
+                    .chars()
+                    .map(|character| (Atom::Literal(character), Repeat::One))
+                    .collect(),
+                anchored_start: false,
+                anchored_end: false,
+            }),
+            SearchKind::Pattern => Self::compile(needle),
+        }
+    }
+
+    fn compile(needle: &str) -> Option<Self> {
+        let mut chars: Vec<char> = needle.chars().collect();
+        let anchored_start: bool = chars.first() == Some(&'^');
+        if anchored_start {
+            chars.remove(0);
+        }
+        let anchored_end: bool =
+            chars.last() == Some(&'$') && (chars.len() < 2 || chars[chars.len() - 2] != '\\');
+        if anchored_end {
+            chars.pop();
+        }
+
+        let mut tokens: Vec<(Atom, Repeat)> = vec![];
+        let mut index: usize = 0;
+        while index < chars.len() {
+            let atom: Atom = match chars[index] {
+                '.' => Atom::Any,
+                '*' | '+' | '?' => return None,
+                '\\' => {
+                    index += 1;
+                    match chars.get(index)? {
+                        'd' => Atom::Digit,
+                        'w' => Atom::Word,
+                        's' => Atom::Space,
+                        &escaped => Atom::Literal(escaped),
+                    }
+                }
+                '[' => {
+                    index += 1;
+                    let negated: bool = chars.get(index) == Some(&'^');
+                    if negated {
+                        index += 1;
+                    }
+                    let mut ranges: Vec<(char, char)> = vec![];
+                    loop {
+                        let low: char = *chars.get(index)?;
+                        if low == ']' && !ranges.is_empty() {
+                            break;
+                        }
+                        if chars.get(index + 1) == Some(&'-')
+                            && chars.get(index + 2).is_some_and(|&high| high != ']')
+                        {
+                            ranges.push((low, chars[index + 2]));
+                            index += 3;
+                        } else {
+                            ranges.push((low, low));
+                            index += 1;
+                        }
+                    }
+                    Atom::Class(ranges, negated)
+                }
+                literal => Atom::Literal(literal),
+            };
+            index += 1;
+            let repeat: Repeat = match chars.get(index) {
+                Some('*') => Repeat::ZeroOrMore,
+                Some('+') => Repeat::OneOrMore,
+                Some('?') => Repeat::ZeroOrOne,
+                _ => Repeat::One,
+            };
+            if repeat != Repeat::One {
+                index += 1;
+            }
+            tokens.push((atom, repeat));
+        }
+
+        Some(Self {
+            tokens,
+            anchored_start,
+            anchored_end,
+        })
+    }
+
+    /// finds the non-overlapping, non-empty matches in the text as (start, length) pairs
+    pub fn find_all(&self, text: &[char]) -> Vec<(usize, usize)> {
+        let mut found: Vec<(usize, usize)> = vec![];
+        let mut start: usize = 0;
+        while start <= text.len() {
+            if self.anchored_start && start > 0 {
+                break;
+            }
+            match self.match_here(&self.tokens, text, start) {
+                Some(end) if end > start => {
+                    found.push((start, end - start));
+                    start = end;
+                }
+                _ => start += 1,
+            }
+        }
+        found
+    }
+
+    /// collects the matches of one row of cells, ignoring the trailing blank cells
+    pub fn find_in_row(&self, line: usize, row: &[CharInfo], matches: &mut Vec<HistoryMatch>) {
+        let mut text: Vec<char> = row.iter().map(|cell| cell.0).collect();
+        while text
+            .last()
+            .is_some_and(|&character| character == ' ' || character == '\0')
+        {
+            text.pop();
+        }
+        for (start, length) in self.find_all(&text) {
+            matches.push(HistoryMatch {
+                line,
+                column: start as u16,
+                length: length as u16,
+            });
+        }
+    }
+
+    fn match_here(
+        &self,
+        tokens: &[(Atom, Repeat)],
+        text: &[char],
+        position: usize,
+    ) -> Option<usize> {
+        let Some(((atom, repeat), rest)) = tokens.split_first() else {
+            return if !self.anchored_end || position == text.len() {
+                Some(position)
+            } else {
+                None
+            };
+        };
+        let (min, max): (usize, usize) = match repeat {
+            Repeat::One => (1, 1),
+            Repeat::ZeroOrOne => (0, 1),
+            Repeat::ZeroOrMore => (0, usize::MAX),
+            Repeat::OneOrMore => (1, usize::MAX),
+        };
+        let mut count: usize = 0;
+        while count < max && position + count < text.len() && atom.matches(text[position + count]) {
+            count += 1;
+        }
+        // greedy, give back one character at a time until the rest matches
+        loop {
+            if count < min {
+                return None;
+            }
+            if let Some(end) = self.match_here(rest, text, position + count) {
+                return Some(end);
+            }
+            if count == 0 {
+                return None;
+            }
+            count -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod search_pattern {
+    use crate::*;
+
+    fn find(needle: &str, kind: SearchKind, text: &str) -> Vec<(usize, usize)> {
+        let text: Vec<char> = text.chars().collect();
+        SearchPattern::new(needle, kind).unwrap().find_all(&text)
+    }
+
+    #[test]
+    fn finds_plain_text() {
+        assert_eq!(
+            find("ab", SearchKind::Text, "abcab.*"),
+            vec![(0, 2), (3, 2)]
+        );
+        assert_eq!(find(".*", SearchKind::Text, "abcab.*"), vec![(5, 2)]);
+    }
+
+    #[test]
+    fn finds_patterns() {
+        assert_eq!(
+            find(r"\d+", SearchKind::Pattern, "a12 b345"),
+            vec![(1, 2), (5, 3)]
+        );
+        assert_eq!(
+            find("^err[a-z]*", SearchKind::Pattern, "error: err"),
+            vec![(0, 5)]
+        );
+        assert_eq!(
+            find("o.?$", SearchKind::Pattern, "foo bar boo"),
+            vec![(9, 2)]
+        );
+        assert_eq!(
+            find("[^ ]+", SearchKind::Pattern, "a bc"),
+            vec![(0, 1), (2, 2)]
+        );
+        assert!(SearchPattern::new("[abc", SearchKind::Pattern).is_none());
+        assert!(SearchPattern::new("*a", SearchKind::Pattern).is_none());
+    }
+}
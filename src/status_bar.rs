@@ -0,0 +1,299 @@
+use crate::constants::*;
+use crate::input::InputEvent;
+use crate::rusted::Rusted;
+use crate::theme::Theme;
+
+/// a key shown at the left of a [StatusBar] with what it does, such as "F1 Help"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyHint {
+    pub key: String,
+    pub label: String,
+    pub virtual_key_code: u16,
+    /// reported when the key is pressed or the hint is clicked
+    pub command: u32,
+}
+
+/// a right-aligned field of a [StatusBar] such as a clock, the editing mode or a progress bar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusSegment {
+    pub text: String,
+    /// the columns wanted, fewer are given when the console is narrow
+    pub width: usize,
+}
+
+/// the x and width of a hint or segment
+type Span = (usize, usize);
+
+/// a status line of key hints on the left and live segments on the right, separated by lines.
+/// segments get the columns the hints leave free but at least half the row, and text that does
+/// not fit is cut with an ellipsis
+#[derive(Debug, Clone)]
+pub struct StatusBar {
+    /// the row the bar is drawn on
+    pub y: i32,
+    #[doc(hidden)]
+    hints: Vec<KeyHint>,
+    #[doc(hidden)]
+    segments: Vec<StatusSegment>,
+}
+
+impl StatusBar {
+    pub fn new(y: i32) -> Self {
+        Self {
+            y,
+            hints: vec![],
+            segments: vec![],
+        }
+    }
+
+    pub fn hint(mut self, key: &str, label: &str, virtual_key_code: u16, command: u32) -> Self {
+        self.hints.push(KeyHint {
+            key: key.to_string(),
+            label: label.to_string(),
+            virtual_key_code,
+            command,
+        });
+        self
+    }
+
+    /// adds an empty segment left of the segments added before it
+    pub fn segment(mut self, width: usize) -> Self {
+        self.segments.push(StatusSegment {
+            text: String::new(),
+            width,
+        });
+        self
+    }
+
+    pub fn hints(&self) -> &[KeyHint] {
+        &self.hints
+    }
+
+    pub fn segments(&self) -> &[StatusSegment] {
+        &self.segments
+    }
+
+    /// changes the text of a segment and draws only that segment when it changed
+    pub fn set_segment(&mut self, ctx: &mut Rusted, index: usize, text: &str) {
+        match self.segments.get_mut(index) {
+            Some(segment) if segment.text != text => segment.text = text.to_string(),
+            _ => return,
+        }
+        let (_, spans) = self.util_layout(ctx.console.size.0 as usize);
+        let theme: Theme = *ctx.theme();
+        ctx.push_state();
+        ctx.set_fgcolor(theme.status.0);
+        ctx.set_bgcolor(theme.status.1);
+        self.util_draw_segment(ctx, index, spans[index]);
+        ctx.pop_state();
+    }
+
+    /// gets a bar of width columns filled in proportion to done of total
+    pub fn progress(done: usize, total: usize, width: usize) -> String {
+        let filled: usize = (done.min(total) * width).checked_div(total).unwrap_or(0);
+        let mut bar: String = BLOCK_FULL.to_string().repeat(filled);
+        bar.push_str(&BLOCK_LIGHT_SHADE.to_string().repeat(width - filled));
+        bar
+    }
+
+    /// draws the whole bar across the console with the status colors of the theme, the keys of
+    /// the hints in the hotkey color
+    pub fn draw(&self, ctx: &mut Rusted) {
+        let columns: usize = ctx.console.size.0 as usize;
+        let (hints, segments) = self.util_layout(columns);
+        let theme: Theme = *ctx.theme();
+        ctx.push_state();
+        ctx.set_fgcolor(theme.status.0);
+        ctx.set_bgcolor(theme.status.1);
+        ctx.outchars(0, self.y, &" ".repeat(columns));
+        for (hint, &(x, width)) in self.hints.iter().zip(hints.iter()) {
+            if width == 0 {
+                continue;
+            }
+            let text: String = format!(" {} {} ", hint.key, hint.label);
+            ctx.outchars(x as i32, self.y, &util_fit_text(&text, width));
+            let key: usize = hint.key.chars().count().min(width.saturating_sub(2));
+            ctx.set_fgcolor(theme.hotkey);
+            ctx.outchars(x as i32 + 1, self.y, &util_fit_text(&hint.key, key));
+            ctx.set_fgcolor(theme.status.0);
+        }
+        for (index, &span) in segments.iter().enumerate() {
+            self.util_draw_segment(ctx, index, span);
+        }
+        ctx.pop_state();
+    }
+
+    /// gets the command of the hint whose key was pressed or that was clicked
+    pub fn handle_event(&self, ctx: &Rusted, event: &InputEvent) -> Option<u32> {
+        match event {
+            InputEvent::Key(key) if key.key_down => self
+                .hints
+                .iter()
+                .find(|hint| hint.virtual_key_code == key.virtual_key_code)
+                .map(|hint| hint.command),
+            InputEvent::Mouse(mouse) if mouse.left_button() && !mouse.moved() => {
+                let (mx, my) = (mouse.position.0 as usize, mouse.position.1 as i32);
+                if my != self.y {
+                    return None;
+                }
+                let (hints, _) = self.util_layout(ctx.console.size.0 as usize);
+                self.hints
+                    .iter()
+                    .zip(hints)
+                    .find(|(_, (x, width))| mx >= *x && mx < x + width)
+                    .map(|(hint, _)| hint.command)
+            }
+            _ => None,
+        }
+    }
+
+    /// draws the separator left of the segment and its text, nothing when it got no columns
+    fn util_draw_segment(&self, ctx: &mut Rusted, index: usize, (x, width): Span) {
+        if width == 0 {
+            return;
+        }
+        ctx.outchar(x as i32 - 1, self.y, BOX_VERTICAL_SINGLE);
+        let text: String = format!(" {}", self.segments[index].text);
+        ctx.outchars(x as i32, self.y, &util_fit_text(&text, width));
+    }
+
+    /// gets the span of each hint and segment on a row of columns. segments are placed from the
+    /// right edge, each after a separator column
+    fn util_layout(&self, columns: usize) -> (Vec<Span>, Vec<Span>) {
+        let hint_widths: Vec<usize> = self
+            .hints
+            .iter()
+            .map(|hint| hint.key.chars().count() + hint.label.chars().count() + 3)
+            .collect();
+        let wanted: usize = hint_widths.iter().sum();
+        let mut budget: usize = columns.saturating_sub(wanted).max(columns / 2);
+        let mut right: usize = columns;
+        let mut segments: Vec<Span> = vec![];
+        for segment in &self.segments {
+            // a segment needs a separator and at least one column
+            let width: usize = (segment.width + 1).min(budget);
+            if width < 2 {
+                segments.push((right, 0));
+                budget = 0;
+                continue;
+            }
+            right -= width;
+            budget -= width;
+            segments.push((right + 1, width - 1));
+        }
+        let mut x: usize = 0;
+        let hints: Vec<Span> = hint_widths
+            .into_iter()
+            .map(|width| {
+                // a hint cut shorter than its key and a space is left out
+                let width: usize = match width.min(right - x) {
+                    width if width < 3 => 0,
+                    width => width,
+                };
+                x += width;
+                (x - width, width)
+            })
+            .collect();
+        (hints, segments)
+    }
+}
+
+/// pads text with spaces to width, or cuts it to width ending in an ellipsis
+fn util_fit_text(text: &str, width: usize) -> String {
+    let length: usize = text.chars().count();
+    if length <= width {
+        return format!("{:<1$}", text, width);
+    }
+    let mut fitted: String = text.chars().take(width.saturating_sub(1)).collect();
+    if width > 0 {
+        fitted.push(ELLIPSIS);
+    }
+    fitted
+}
+
+#[cfg(test)]
+mod status_line {
+    use crate::test_util::row;
+    use crate::*;
+
+    fn bar() -> StatusBar {
+        StatusBar::new(24)
+            .hint("F1", "Help", VK_F1, 1)
+            .hint("F10", "Menu", VK_F10, 10)
+            .segment(6)
+            .segment(4)
+    }
+
+    #[test]
+    fn draws_hints_left_and_segments_right() {
+        let mut rusted = Rusted::new();
+        let mut status: StatusBar = bar();
+        status.draw(&mut rusted);
+        status.set_segment(&mut rusted, 0, "12:30");
+        status.set_segment(&mut rusted, 1, "INS");
+
+        let line: String = row(&rusted, 24, 80);
+        assert!(line.starts_with(" F1 Help  F10 Menu  "));
+        assert!(line.ends_with("\u{2502} INS\u{2502} 12:30"));
+        assert_eq!(
+            rusted.console.get_cell(Coord(1, 24)).unwrap().1,
+            Rusted::color_attribute(1, 7)
+        );
+        assert_eq!(
+            rusted.console.get_cell(Coord(4, 24)).unwrap().1,
+            Rusted::color_attribute(0, 7)
+        );
+    }
+
+    #[test]
+    fn updates_a_segment_without_touching_the_rest() {
+        let mut rusted = Rusted::new();
+        let mut status: StatusBar = bar();
+        status.draw(&mut rusted);
+        rusted.outchars(0, 24, "#");
+        rusted.outchars(70, 24, "#");
+
+        status.set_segment(&mut rusted, 0, &StatusBar::progress(1, 2, 4));
+
+        let line: String = row(&rusted, 24, 80);
+        assert!(line.starts_with("#F1"));
+        assert!(line.ends_with("\u{2502} #  \u{2502} \u{2588}\u{2588}\u{2591}\u{2591} "));
+    }
+
+    #[test]
+    fn cuts_hints_and_segments_on_narrow_consoles() {
+        let mut rusted = Rusted::new();
+        rusted.resize(20, 25, ResizeMode::Crop);
+        let mut status: StatusBar = bar().segment(8);
+        status.draw(&mut rusted);
+        status.set_segment(&mut rusted, 0, "12:30");
+        status.set_segment(&mut rusted, 1, "INS");
+        status.set_segment(&mut rusted, 2, "untitled");
+
+        assert_eq!(
+            row(&rusted, 24, 20),
+            " F1 Help  \u{2502} \u{2026}\u{2502} 12:30"
+        );
+    }
+
+    #[test]
+    fn activates_hints_by_key_or_click() {
+        let rusted = Rusted::new();
+        let status: StatusBar = bar();
+        let click = |x: u16| {
+            InputEvent::Mouse(MouseEvent::new(
+                Coord(x, 24),
+                FROM_LEFT_1ST_BUTTON_PRESSED,
+                0,
+            ))
+        };
+
+        assert_eq!(
+            status.handle_event(&rusted, &InputEvent::Key(KeyEvent::new(VK_F10, '\0'))),
+            Some(10)
+        );
+        assert_eq!(status.handle_event(&rusted, &click(3)), Some(1));
+        assert_eq!(status.handle_event(&rusted, &click(12)), Some(10));
+        assert_eq!(status.handle_event(&rusted, &click(40)), None);
+    }
+}
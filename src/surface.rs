@@ -0,0 +1,324 @@
+use crate::console::{BlitMode, CharInfo, Console};
+use crate::geometry::{Coord, Rect, Size};
+use crate::window::Window;
+
+/// a grid of cells that can be drawn on
+pub trait Surface {
+    /// gets the number of columns and rows
+    fn size(&self) -> Size;
+
+    /// gets the cell at coord, None when coord is outside of the surface
+    fn get_cell(&self, coord: Coord) -> Option<CharInfo>;
+
+    /// sets the cell at coord, false when coord is outside of the surface
+    fn set_cell(&mut self, coord: Coord, cell: CharInfo) -> bool;
+
+    /// gets the rectangle covering the whole surface
+    fn bounds(&self) -> Rect {
+        Rect::from_position_size(Coord(0, 0), self.size())
+    }
+
+    /// gets the cells of row as one slice, None when the surface does not store its rows
+    /// contiguously or row is outside of the surface
+    fn row_cells(&self, _row: u16) -> Option<&[CharInfo]> {
+        None
+    }
+
+    /// gets the cells start..end of row as one slice to modify, None when the surface does not
+    /// store its rows contiguously or the span is not inside of the surface
+    fn row_cells_mut(&mut self, _row: u16, _start: u16, _end: u16) -> Option<&mut [CharInfo]> {
+        None
+    }
+
+    /// sets every cell of rect, the parts outside of the surface are skipped
+    fn fill_rect(&mut self, rect: Rect, cell: CharInfo) {
+        let rect: Rect = rect.clip_to(&self.bounds());
+        for row in rect.1..rect.3 {
+            match self.row_cells_mut(row, rect.0, rect.2) {
+                Some(cells) => cells.fill(cell),
+                None => {
+                    for column in rect.0..rect.2 {
+                        self.set_cell(Coord(column, row), cell);
+                    }
+                }
+            }
+        }
+    }
+
+    /// combines every cell of rect with cell according to mode, see [BlitMode::blend]
+    fn blend_rect(&mut self, rect: Rect, cell: CharInfo, mode: BlitMode) {
+        let rect: Rect = rect.clip_to(&self.bounds());
+        for row in rect.1..rect.3 {
+            match self.row_cells_mut(row, rect.0, rect.2) {
+                Some(slots) => {
+                    for slot in slots {
+                        *slot = mode.blend(*slot, cell);
+                    }
+                }
+                None => {
+                    for column in rect.0..rect.2 {
+                        if let Some(under) = self.get_cell(Coord(column, row)) {
+                            self.set_cell(Coord(column, row), mode.blend(under, cell));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// darkens the attributes of rect and keeps the characters, see [crate::Attribute::darkened]
+    fn darken_rect(&mut self, rect: Rect) {
+        self.blend_rect(rect, CharInfo::default(), BlitMode::Darken);
+    }
+
+    /// copies the src region of source to dst, only the part inside both surfaces is copied
+    fn blit(&mut self, source: &dyn Surface, src: Rect, dst: Coord) {
+        let src: Rect = src.clip_to(&source.bounds());
+        let pasted: Rect = Rect::from_position_size(dst, src.size()).clip_to(&self.bounds());
+        let from_column: usize = (src.0 + (pasted.0 - dst.0)) as usize;
+        let width: usize = pasted.width() as usize;
+        for row in pasted.1..pasted.3 {
+            let from_row: u16 = src.1 + (row - dst.1);
+            let cells: Option<&[CharInfo]> = source
+                .row_cells(from_row)
+                .and_then(|cells| cells.get(from_column..from_column + width));
+            if let Some(cells) = cells {
+                if let Some(slots) = self.row_cells_mut(row, pasted.0, pasted.2) {
+                    slots.copy_from_slice(cells);
+                    continue;
+                }
+            }
+            for column in pasted.0..pasted.2 {
+                let from: Coord = Coord(src.0 + (column - dst.0), from_row);
+                if let Some(cell) = source.get_cell(from) {
+                    self.set_cell(Coord(column, row), cell);
+                }
+            }
+        }
+    }
+
+    /// borrows the region of the surface as a surface with its own local coordinates
+    fn sub_surface(&mut self, region: Rect) -> SubSurface<'_>
+    where
+        Self: Sized,
+    {
+        SubSurface::new(self, region)
+    }
+}
+
+/// a rectangular region of another surface, coordinates are relative to the top-left of the region
+pub struct SubSurface<'a> {
+    #[doc(hidden)]
+    parent: &'a mut dyn Surface,
+    #[doc(hidden)]
+    region: Rect,
+}
+
+impl<'a> SubSurface<'a> {
+    /// the region is clipped to the bounds of the parent
+    pub fn new(parent: &'a mut dyn Surface, region: Rect) -> Self {
+        let region: Rect = region.clip_to(&parent.bounds());
+        Self { parent, region }
+    }
+
+    /// gets the region of the parent that is covered
+    pub fn region(&self) -> Rect {
+        self.region
+    }
+
+    fn util_to_parent(&self, coord: Coord) -> Option<Coord> {
+        let parent: Coord = coord + self.region.position();
+        if coord.0 < self.region.width() && coord.1 < self.region.height() {
+            Some(parent)
+        } else {
+            None
+        }
+    }
+}
+
+impl Surface for SubSurface<'_> {
+    fn size(&self) -> Size {
+        self.region.size()
+    }
+
+    fn get_cell(&self, coord: Coord) -> Option<CharInfo> {
+        self.util_to_parent(coord)
+            .and_then(|coord| self.parent.get_cell(coord))
+    }
+
+    fn set_cell(&mut self, coord: Coord, cell: CharInfo) -> bool {
+        match self.util_to_parent(coord) {
+            Some(coord) => self.parent.set_cell(coord, cell),
+            None => false,
+        }
+    }
+
+    fn row_cells(&self, row: u16) -> Option<&[CharInfo]> {
+        if row >= self.region.height() {
+            return None;
+        }
+        let (left, right) = (self.region.0 as usize, self.region.2 as usize);
+        self.parent
+            .row_cells(self.region.1 + row)
+            .and_then(|cells| cells.get(left..right))
+    }
+
+    fn row_cells_mut(&mut self, row: u16, start: u16, end: u16) -> Option<&mut [CharInfo]> {
+        if row >= self.region.height() || start > end || end > self.region.width() {
+            return None;
+        }
+        let left: u16 = self.region.0;
+        self.parent
+            .row_cells_mut(self.region.1 + row, left + start, left + end)
+    }
+}
+
+impl Surface for Console {
+    fn size(&self) -> Size {
+        Size::from(self.size)
+    }
+
+    fn get_cell(&self, coord: Coord) -> Option<CharInfo> {
+        if coord.0 >= self.size.0 {
+            return None;
+        }
+        let index: usize = (coord.0 as usize) + (coord.1 as usize) * (self.size.0 as usize);
+        self.buffer.get(index).copied()
+    }
+
+    fn set_cell(&mut self, coord: Coord, cell: CharInfo) -> bool {
+        if coord.0 >= self.size.0 {
+            return false;
+        }
+        let index: usize = (coord.0 as usize) + (coord.1 as usize) * (self.size.0 as usize);
+        match self.buffer.get_mut(index) {
+            Some(slot) => {
+                *slot = cell;
+                self.mark_dirty(Rect(coord.0, coord.1, coord.0 + 1, coord.1 + 1));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn row_cells(&self, row: u16) -> Option<&[CharInfo]> {
+        util_row_span(&self.buffer, self.size, row, 0, self.size.0).map(|span| &self.buffer[span])
+    }
+
+    /// the span is marked dirty
+    fn row_cells_mut(&mut self, row: u16, start: u16, end: u16) -> Option<&mut [CharInfo]> {
+        let span = util_row_span(&self.buffer, self.size, row, start, end)?;
+        self.mark_dirty(Rect(start, row, end, row + 1));
+        Some(&mut self.buffer[span])
+    }
+}
+
+/// draws into the back buffer of the window, the cells that were saved under it
+impl Surface for Window {
+    fn size(&self) -> Size {
+        let (w, h) = self.buffer_size();
+        Size(w as u16, h as u16)
+    }
+
+    fn get_cell(&self, coord: Coord) -> Option<CharInfo> {
+        let Size(width, height) = Surface::size(self);
+        if coord.0 >= width || coord.1 >= height {
+            return None;
+        }
+        let index: usize = (coord.0 as usize) + (coord.1 as usize) * (width as usize);
+        self.buffer.get(index).copied()
+    }
+
+    fn set_cell(&mut self, coord: Coord, cell: CharInfo) -> bool {
+        let Size(width, height) = Surface::size(self);
+        if coord.0 >= width || coord.1 >= height {
+            return false;
+        }
+        let index: usize = (coord.0 as usize) + (coord.1 as usize) * (width as usize);
+        match self.buffer.get_mut(index) {
+            Some(slot) => {
+                *slot = cell;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn row_cells(&self, row: u16) -> Option<&[CharInfo]> {
+        let Size(width, _) = Surface::size(self);
+        util_row_span(
+            &self.buffer,
+            Coord::from(Surface::size(self)),
+            row,
+            0,
+            width,
+        )
+        .map(|span| &self.buffer[span])
+    }
+
+    fn row_cells_mut(&mut self, row: u16, start: u16, end: u16) -> Option<&mut [CharInfo]> {
+        let size: Coord = Coord::from(Surface::size(self));
+        let span = util_row_span(&self.buffer, size, row, start, end)?;
+        Some(&mut self.buffer[span])
+    }
+}
+
+/// gets the buffer indices of the cells start..end of row in a buffer of size
+fn util_row_span(
+    buffer: &[CharInfo],
+    size: Coord,
+    row: u16,
+    start: u16,
+    end: u16,
+) -> Option<std::ops::Range<usize>> {
+    if row >= size.1 || start > end || end > size.0 {
+        return None;
+    }
+    let row_start: usize = (row as usize) * (size.0 as usize);
+    let span = (row_start + start as usize)..(row_start + end as usize);
+    if span.end > buffer.len() {
+        return None;
+    }
+    Some(span)
+}
+
+#[cfg(test)]
+mod sub_surface {
+    use crate::*;
+
+    fn text(surface: &dyn Surface, row: u16) -> String {
+        (0..surface.size().0)
+            .map(|column| surface.get_cell(Coord(column, row)).unwrap().0)
+            .collect()
+    }
+
+    #[test]
+    fn draws_through_sub_surfaces_in_local_coordinates() {
+        let mut console: Console = Console::new(6, 3);
+        console.fill_rect(console.bounds(), CharInfo('.', Attribute(7)));
+
+        let mut region: SubSurface = console.sub_surface(Rect(2, 1, 8, 3));
+        assert_eq!(region.size(), Size(4, 2));
+        region.fill_rect(Rect(1, 0, 3, 5), CharInfo('#', Attribute(1)));
+        assert!(!region.set_cell(Coord(4, 0), CharInfo('!', Attribute(1))));
+
+        assert_eq!(text(&console, 0), "......");
+        assert_eq!(text(&console, 1), "...##.");
+        assert_eq!(text(&console, 2), "...##.");
+    }
+
+    #[test]
+    fn blits_between_surfaces() {
+        let mut console: Console = Console::new(4, 2);
+        let mut window: Window = Window::new(0, 0, 3, 1);
+        window.fill_rect(window.bounds(), CharInfo('w', Attribute(2)));
+
+        console.blit(&window, Rect(0, 0, 3, 1), Coord(2, 1));
+
+        assert_eq!(text(&console, 1), "\0\0ww");
+        assert_eq!(
+            console.get_cell(Coord(2, 1)),
+            Some(CharInfo('w', Attribute(2)))
+        );
+    }
+}
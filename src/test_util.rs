@@ -0,0 +1,21 @@
+use crate::geometry::Coord;
+use crate::input::{InputEvent, KeyEvent};
+use crate::rusted::Rusted;
+use crate::surface::Surface;
+
+/// gets the characters of the first width cells of row y of the console
+pub(crate) fn row(rusted: &Rusted, y: u16, width: u16) -> String {
+    cells(rusted, 0, y, width)
+}
+
+/// gets the characters of width cells of row y starting at column x
+pub(crate) fn cells(rusted: &Rusted, x: u16, y: u16, width: u16) -> String {
+    (x..x + width)
+        .map(|x| rusted.console.get_cell(Coord(x, y)).unwrap().0)
+        .collect()
+}
+
+/// a key press of code with the modifier flags of the control key state
+pub(crate) fn key(code: u16, modifiers: u32) -> InputEvent {
+    InputEvent::Key(KeyEvent::new(code, '\0').with_modifiers(modifiers))
+}
@@ -0,0 +1,859 @@
+use crate::console::set_console_cursor_position;
+use crate::constants::*;
+use crate::geometry::Coord;
+use crate::input::{InputEvent, KeyEvent};
+use crate::rusted::Rusted;
+
+/// a position in the text of a [TextArea], column counts characters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TextPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl TextPosition {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+/// one undoable change, text was removed at start and inserted in its place
+#[derive(Debug, Clone)]
+struct Edit {
+    start: TextPosition,
+    removed: String,
+    inserted: String,
+    cursor_before: TextPosition,
+}
+
+/// a scrolling multi-line editor with selection, an in-app clipboard, undo and redo, soft word
+/// wrap, line numbers and find and replace. the text is kept as lines and the wrapping of each
+/// line is cached, so an edit only lays out the lines it touched
+#[derive(Debug, Clone)]
+pub struct TextArea {
+    /// x, y, w, h of the area relative to the drawing origin, usually a window client rect
+    pub rect: (i32, i32, i32, i32),
+    pub focused: bool,
+    #[doc(hidden)]
+    lines: Vec<Vec<char>>,
+    #[doc(hidden)]
+    cursor: TextPosition,
+    /// the other end of the selection
+    #[doc(hidden)]
+    anchor: Option<TextPosition>,
+    #[doc(hidden)]
+    clipboard: String,
+    #[doc(hidden)]
+    undo: Vec<Edit>,
+    #[doc(hidden)]
+    redo: Vec<Edit>,
+    #[doc(hidden)]
+    word_wrap: bool,
+    #[doc(hidden)]
+    line_numbers: bool,
+    /// the start columns of the rows of each line, None when the line must be laid out again
+    #[doc(hidden)]
+    rows: Vec<Option<Vec<usize>>>,
+    /// the text width the cached rows were laid out for
+    #[doc(hidden)]
+    rows_width: usize,
+    /// the line and row shown at the top
+    #[doc(hidden)]
+    scroll: (usize, usize),
+    /// the first column shown when word wrap is off
+    #[doc(hidden)]
+    scroll_column: usize,
+}
+
+impl TextArea {
+    pub fn new(rect: (i32, i32, i32, i32)) -> Self {
+        Self {
+            rect,
+            focused: true,
+            lines: vec![vec![]],
+            cursor: TextPosition::default(),
+            anchor: None,
+            clipboard: String::new(),
+            undo: vec![],
+            redo: vec![],
+            word_wrap: true,
+            line_numbers: false,
+            rows: vec![None],
+            rows_width: 0,
+            scroll: (0, 0),
+            scroll_column: 0,
+        }
+    }
+
+    pub fn word_wrap(mut self, word_wrap: bool) -> Self {
+        self.word_wrap = word_wrap;
+        self.util_invalidate_rows();
+        self
+    }
+
+    pub fn line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    pub fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// replaces the whole text, clearing the selection and the undo history
+    pub fn set_text(&mut self, text: &str) {
+        self.lines = text
+            .split('\n')
+            .map(|line| line.chars().collect())
+            .collect();
+        self.rows = vec![None; self.lines.len()];
+        self.cursor = TextPosition::default();
+        self.anchor = None;
+        self.undo.clear();
+        self.redo.clear();
+        self.scroll = (0, 0);
+        self.scroll_column = 0;
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn cursor(&self) -> TextPosition {
+        self.cursor
+    }
+
+    /// moves the cursor, clamped to the text, and clears the selection
+    pub fn set_cursor(&mut self, position: TextPosition) {
+        self.cursor = self.util_clamp(position);
+        self.anchor = None;
+        self.util_scroll_to_cursor();
+    }
+
+    /// gets the start and end of the selection, None when nothing is selected
+    pub fn selection(&self) -> Option<(TextPosition, TextPosition)> {
+        let anchor: TextPosition = self.anchor?;
+        if anchor == self.cursor {
+            return None;
+        }
+        Some((anchor.min(self.cursor), anchor.max(self.cursor)))
+    }
+
+    /// selects from start to end, the cursor is placed at end
+    pub fn select(&mut self, start: TextPosition, end: TextPosition) {
+        self.anchor = Some(self.util_clamp(start));
+        self.cursor = self.util_clamp(end);
+        self.util_scroll_to_cursor();
+    }
+
+    pub fn selected_text(&self) -> String {
+        match self.selection() {
+            Some((start, end)) => self.util_text_between(start, end),
+            None => String::new(),
+        }
+    }
+
+    pub fn clipboard(&self) -> &str {
+        &self.clipboard
+    }
+
+    pub fn copy(&mut self) {
+        if self.selection().is_some() {
+            self.clipboard = self.selected_text();
+        }
+    }
+
+    pub fn cut(&mut self) {
+        if let Some((start, end)) = self.selection() {
+            self.clipboard = self.selected_text();
+            self.util_edit(start, end, "");
+        }
+    }
+
+    pub fn paste(&mut self) {
+        let text: String = self.clipboard.clone();
+        self.insert(&text);
+    }
+
+    /// replaces the selection with text, or inserts it at the cursor
+    pub fn insert(&mut self, text: &str) {
+        let (start, end) = self.selection().unwrap_or((self.cursor, self.cursor));
+        self.util_edit(start, end, text);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let Some(edit) = self.undo.pop() else {
+            return false;
+        };
+        let inserted_end: TextPosition = util_end_of(edit.start, &edit.inserted);
+        self.util_remove(edit.start, inserted_end);
+        self.util_insert(edit.start, &edit.removed);
+        self.cursor = edit.cursor_before;
+        self.anchor = None;
+        self.redo.push(edit);
+        self.util_scroll_to_cursor();
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let Some(edit) = self.redo.pop() else {
+            return false;
+        };
+        let removed_end: TextPosition = util_end_of(edit.start, &edit.removed);
+        self.util_remove(edit.start, removed_end);
+        self.cursor = self.util_insert(edit.start, &edit.inserted);
+        self.anchor = None;
+        self.undo.push(edit);
+        self.util_scroll_to_cursor();
+        true
+    }
+
+    /// selects the next occurrence of needle after the cursor, continuing from the top.
+    /// false when there is none
+    pub fn find(&mut self, needle: &str) -> bool {
+        let needle: Vec<char> = needle.chars().collect();
+        if needle.is_empty() || needle.contains(&'\n') {
+            return false;
+        }
+        let count: usize = self.lines.len();
+        for offset in 0..=count {
+            let line: usize = (self.cursor.line + offset) % count;
+            let from: usize = if offset == 0 { self.cursor.column } else { 0 };
+            let text: &Vec<char> = &self.lines[line];
+            let found: Option<usize> = (from..=text.len().saturating_sub(needle.len()))
+                .find(|&column| text[column..].starts_with(&needle));
+            if let Some(column) = found {
+                if offset == count && column >= self.cursor.column {
+                    break;
+                }
+                self.select(
+                    TextPosition::new(line, column),
+                    TextPosition::new(line, column + needle.len()),
+                );
+                return true;
+            }
+        }
+        false
+    }
+
+    /// replaces the selection when it is needle, then selects the next occurrence.
+    /// false when there is no further occurrence
+    pub fn replace(&mut self, needle: &str, replacement: &str) -> bool {
+        if !needle.is_empty() && self.selected_text() == needle {
+            self.insert(replacement);
+        }
+        self.find(needle)
+    }
+
+    /// replaces every occurrence of needle and returns how many were replaced, all replacements
+    /// are undone together
+    pub fn replace_all(&mut self, needle: &str, replacement: &str) -> usize {
+        let length: usize = needle.chars().count();
+        if length == 0 || needle.contains('\n') {
+            return 0;
+        }
+        let pattern: Vec<char> = needle.chars().collect();
+        let mut found: Vec<TextPosition> = Vec::new();
+        for (line, text) in self.lines.iter().enumerate() {
+            let mut column: usize = 0;
+            while column + length <= text.len() {
+                if text[column..].starts_with(&pattern) {
+                    found.push(TextPosition::new(line, column));
+                    column += length;
+                } else {
+                    column += 1;
+                }
+            }
+        }
+        let (Some(&first), Some(&last)) = (found.first(), found.last()) else {
+            return 0;
+        };
+        // one edit from the first to the last occurrence, the needle never spans lines so the
+        // occurrences in the joined text are the ones found above
+        let end: TextPosition = TextPosition::new(last.line, last.column + length);
+        let replaced: String = self
+            .util_text_between(first, end)
+            .replace(needle, replacement);
+        self.util_edit(first, end, &replaced);
+        found.len()
+    }
+
+    /// handles editing keys, Ctrl+A, C, X, V, Z and Y. Shift with the movement keys selects.
+    /// returns false when the event was not used
+    pub fn handle_event(&mut self, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::Key(key) if key.key_down => self.handle_key(key),
+            _ => false,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        if key.ctrl() {
+            match key.virtual_key_code {
+                VK_A => {
+                    let end: TextPosition =
+                        TextPosition::new(self.lines.len() - 1, self.lines.last().unwrap().len());
+                    self.select(TextPosition::default(), end);
+                }
+                VK_C => self.copy(),
+                VK_X => self.cut(),
+                VK_V => self.paste(),
+                VK_Z => {
+                    self.undo();
+                }
+                VK_Y => {
+                    self.redo();
+                }
+                VK_HOME => self.util_move(key, TextPosition::default()),
+                VK_END => {
+                    let end: TextPosition =
+                        TextPosition::new(self.lines.len() - 1, self.lines.last().unwrap().len());
+                    self.util_move(key, end);
+                }
+                _ => return false,
+            }
+            self.util_scroll_to_cursor();
+            return true;
+        }
+        let cursor: TextPosition = self.cursor;
+        let line_length: usize = self.lines[cursor.line].len();
+        let page: usize = (self.rect.3.max(2) - 1) as usize;
+        match key.virtual_key_code {
+            VK_LEFT => {
+                let target: TextPosition = if cursor.column > 0 {
+                    TextPosition::new(cursor.line, cursor.column - 1)
+                } else if cursor.line > 0 {
+                    TextPosition::new(cursor.line - 1, self.lines[cursor.line - 1].len())
+                } else {
+                    cursor
+                };
+                self.util_move(key, target);
+            }
+            VK_RIGHT => {
+                let target: TextPosition = if cursor.column < line_length {
+                    TextPosition::new(cursor.line, cursor.column + 1)
+                } else if cursor.line + 1 < self.lines.len() {
+                    TextPosition::new(cursor.line + 1, 0)
+                } else {
+                    cursor
+                };
+                self.util_move(key, target);
+            }
+            VK_UP => {
+                let target: TextPosition = self.util_vertical(cursor, 1, true);
+                self.util_move(key, target);
+            }
+            VK_DOWN => {
+                let target: TextPosition = self.util_vertical(cursor, 1, false);
+                self.util_move(key, target);
+            }
+            VK_PRIOR => {
+                let target: TextPosition = self.util_vertical(cursor, page, true);
+                self.util_move(key, target);
+            }
+            VK_NEXT => {
+                let target: TextPosition = self.util_vertical(cursor, page, false);
+                self.util_move(key, target);
+            }
+            VK_HOME => self.util_move(key, TextPosition::new(cursor.line, 0)),
+            VK_END => self.util_move(key, TextPosition::new(cursor.line, line_length)),
+            VK_BACK => match self.selection() {
+                Some((start, end)) => self.util_edit(start, end, ""),
+                None if cursor != TextPosition::default() => {
+                    let start: TextPosition = if cursor.column > 0 {
+                        TextPosition::new(cursor.line, cursor.column - 1)
+                    } else {
+                        TextPosition::new(cursor.line - 1, self.lines[cursor.line - 1].len())
+                    };
+                    self.util_edit(start, cursor, "");
+                }
+                None => {}
+            },
+            VK_DELETE => match self.selection() {
+                Some((start, end)) => self.util_edit(start, end, ""),
+                None if cursor.column < line_length => self.util_edit(
+                    cursor,
+                    TextPosition::new(cursor.line, cursor.column + 1),
+                    "",
+                ),
+                None if cursor.line + 1 < self.lines.len() => {
+                    self.util_edit(cursor, TextPosition::new(cursor.line + 1, 0), "")
+                }
+                None => {}
+            },
+            VK_RETURN => self.insert("\n"),
+            _ if key.character >= ' ' && !key.alt() => {
+                let mut encoded: [u8; 4] = [0; 4];
+                self.insert(key.character.encode_utf8(&mut encoded));
+            }
+            _ => return false,
+        }
+        self.util_scroll_to_cursor();
+        true
+    }
+
+    /// draws the visible rows, the line numbers and the selection with the colors of the theme
+    /// and places the cursor
+    pub fn draw(&mut self, ctx: &mut Rusted) {
+        let (x, y, w, h) = self.rect;
+        let gutter: usize = self.util_gutter_width();
+        let width: usize = self.util_text_width();
+        let selection: Option<(TextPosition, TextPosition)> = self.selection();
+        let (normal, selected) = if self.focused {
+            (ctx.theme().focused, ctx.theme().selected)
+        } else {
+            (ctx.theme().input, ctx.theme().selected)
+        };
+        let mut cursor_cell: Option<(i32, i32)> = None;
+        // a resize or a narrower wrap can leave the scroll past the text
+        self.scroll.0 = self.scroll.0.min(self.lines.len() - 1);
+
+        ctx.push_state();
+        ctx.set_clip(x, y, w, h);
+        let mut row: Option<(usize, usize)> = Some(self.scroll);
+        for screen_row in 0..h.max(0) {
+            ctx.set_fgcolor(normal.0);
+            ctx.set_bgcolor(normal.1);
+            let row_y: i32 = y + screen_row;
+            let Some((line, line_row)) = row else {
+                ctx.outchars(x, row_y, &" ".repeat(gutter + width));
+                continue;
+            };
+            if gutter > 0 {
+                let number: String = if line_row == 0 {
+                    format!("{:>1$} ", line + 1, gutter - 1)
+                } else {
+                    " ".repeat(gutter)
+                };
+                ctx.outchars(x, row_y, &number);
+            }
+            let (start, end) = self.util_row_span(line, line_row);
+            let (start, end) = if self.word_wrap {
+                (start, end)
+            } else {
+                let start: usize = self.scroll_column.min(end);
+                (start, end.min(start + width))
+            };
+            for column in 0..width {
+                let index: usize = start + column;
+                let character: char = if index < end {
+                    self.lines[line][index]
+                } else {
+                    ' '
+                };
+                let position: TextPosition = TextPosition::new(line, index);
+                let in_selection: bool = index < end
+                    && selection.is_some_and(|(from, to)| position >= from && position < to);
+                let (fgc, bgc) = if in_selection { selected } else { normal };
+                ctx.set_fgcolor(fgc);
+                ctx.set_bgcolor(bgc);
+                ctx.outchar(x + (gutter + column) as i32, row_y, character);
+                if position == self.cursor {
+                    cursor_cell = Some((x + (gutter + column) as i32, row_y));
+                }
+            }
+            if self.cursor.line == line && self.cursor.column == end && end - start == width {
+                cursor_cell = cursor_cell.or(Some((x + (gutter + width) as i32 - 1, row_y)));
+            }
+            row = self.util_next_row((line, line_row));
+        }
+        let console_cursor: Coord = ctx.console.cursor;
+        ctx.pop_state();
+        set_console_cursor_position(&mut ctx.console, console_cursor);
+        if let (true, Some((cursor_x, cursor_y))) = (self.focused, cursor_cell) {
+            ctx.set_xy(cursor_x, cursor_y);
+        }
+    }
+
+    fn util_gutter_width(&self) -> usize {
+        if self.line_numbers {
+            self.lines.len().to_string().len() + 1
+        } else {
+            0
+        }
+    }
+
+    fn util_text_width(&self) -> usize {
+        (self.rect.2.max(0) as usize)
+            .saturating_sub(self.util_gutter_width())
+            .max(1)
+    }
+
+    fn util_invalidate_rows(&mut self) {
+        self.rows = vec![None; self.lines.len()];
+    }
+
+    /// gets the start columns of the rows of the line, laying it out when needed
+    fn util_rows(&mut self, line: usize) -> &Vec<usize> {
+        let width: usize = self.util_text_width();
+        if width != self.rows_width {
+            self.rows_width = width;
+            self.util_invalidate_rows();
+        }
+        if self.rows[line].is_none() {
+            let rows: Vec<usize> = if self.word_wrap {
+                util_wrap(&self.lines[line], width)
+            } else {
+                vec![0]
+            };
+            self.rows[line] = Some(rows);
+        }
+        self.rows[line].as_ref().unwrap()
+    }
+
+    /// gets the start and end column of a row of the line
+    fn util_row_span(&mut self, line: usize, row: usize) -> (usize, usize) {
+        let length: usize = self.lines[line].len();
+        let rows: &Vec<usize> = self.util_rows(line);
+        let start: usize = rows[row.min(rows.len() - 1)];
+        let end: usize = rows.get(row + 1).copied().unwrap_or(length);
+        (start, end)
+    }
+
+    /// gets the row of the line the position is shown on
+    fn util_row_of(&mut self, position: TextPosition) -> usize {
+        let rows: &Vec<usize> = self.util_rows(position.line);
+        rows.iter()
+            .rposition(|&start| start <= position.column)
+            .unwrap_or(0)
+    }
+
+    fn util_next_row(&mut self, row: (usize, usize)) -> Option<(usize, usize)> {
+        let (line, line_row) = row;
+        if line_row + 1 < self.util_rows(line).len() {
+            Some((line, line_row + 1))
+        } else if line + 1 < self.lines.len() {
+            Some((line + 1, 0))
+        } else {
+            None
+        }
+    }
+
+    fn util_prev_row(&mut self, row: (usize, usize)) -> Option<(usize, usize)> {
+        let (line, line_row) = row;
+        if line_row > 0 {
+            Some((line, line_row - 1))
+        } else if line > 0 {
+            let count: usize = self.util_rows(line - 1).len();
+            Some((line - 1, count - 1))
+        } else {
+            None
+        }
+    }
+
+    /// moves the position count rows up or down, keeping its column within the row
+    fn util_vertical(&mut self, position: TextPosition, count: usize, up: bool) -> TextPosition {
+        let mut row: (usize, usize) = (position.line, self.util_row_of(position));
+        let offset: usize = position.column - self.util_row_span(row.0, row.1).0;
+        for _ in 0..count {
+            let next: Option<(usize, usize)> = if up {
+                self.util_prev_row(row)
+            } else {
+                self.util_next_row(row)
+            };
+            match next {
+                Some(next) => row = next,
+                None => break,
+            }
+        }
+        let (start, end) = self.util_row_span(row.0, row.1);
+        // the last column of a wrapped row belongs to the next row
+        let last: usize = if end < self.lines[row.0].len() {
+            end.saturating_sub(1).max(start)
+        } else {
+            end
+        };
+        TextPosition::new(row.0, (start + offset).min(last))
+    }
+
+    /// moves the cursor, extending the selection with Shift and clearing it otherwise
+    fn util_move(&mut self, key: &KeyEvent, target: TextPosition) {
+        if key.shift() {
+            if self.anchor.is_none() {
+                self.anchor = Some(self.cursor);
+            }
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = target;
+    }
+
+    fn util_scroll_to_cursor(&mut self) {
+        let height: usize = self.rect.3.max(1) as usize;
+        let cursor_row: (usize, usize) = (self.cursor.line, self.util_row_of(self.cursor));
+        if cursor_row < self.scroll {
+            self.scroll = cursor_row;
+        } else {
+            // the topmost row that still shows the cursor on the last visible row
+            let mut top: (usize, usize) = cursor_row;
+            for _ in 1..height {
+                match self.util_prev_row(top) {
+                    Some(row) => top = row,
+                    None => break,
+                }
+            }
+            if top > self.scroll {
+                self.scroll = top;
+            }
+        }
+        if !self.word_wrap {
+            let width: usize = self.util_text_width();
+            if self.cursor.column < self.scroll_column {
+                self.scroll_column = self.cursor.column;
+            } else if self.cursor.column >= self.scroll_column + width {
+                self.scroll_column = self.cursor.column + 1 - width;
+            }
+        }
+    }
+
+    fn util_clamp(&self, position: TextPosition) -> TextPosition {
+        let line: usize = position.line.min(self.lines.len() - 1);
+        TextPosition::new(line, position.column.min(self.lines[line].len()))
+    }
+
+    fn util_text_between(&self, start: TextPosition, end: TextPosition) -> String {
+        if start.line == end.line {
+            return self.lines[start.line][start.column..end.column]
+                .iter()
+                .collect();
+        }
+        let mut text: String = self.lines[start.line][start.column..].iter().collect();
+        for line in &self.lines[start.line + 1..end.line] {
+            text.push('\n');
+            text.extend(line.iter());
+        }
+        text.push('\n');
+        text.extend(self.lines[end.line][..end.column].iter());
+        text
+    }
+
+    /// replaces the text between start and end, recording the change for undo
+    fn util_edit(&mut self, start: TextPosition, end: TextPosition, text: &str) {
+        if start == end && text.is_empty() {
+            return;
+        }
+        let cursor_before: TextPosition = self.cursor;
+        let removed: String = self.util_remove(start, end);
+        self.cursor = self.util_insert(start, text);
+        self.anchor = None;
+        self.redo.clear();
+        self.undo.push(Edit {
+            start,
+            removed,
+            inserted: text.to_string(),
+            cursor_before,
+        });
+        self.util_scroll_to_cursor();
+    }
+
+    /// removes the text between start and end and returns it
+    fn util_remove(&mut self, start: TextPosition, end: TextPosition) -> String {
+        let removed: String = self.util_text_between(start, end);
+        if start.line == end.line {
+            self.lines[start.line].drain(start.column..end.column);
+        } else {
+            let tail: Vec<char> = self.lines[end.line][end.column..].to_vec();
+            self.lines[start.line].truncate(start.column);
+            self.lines[start.line].extend(tail);
+            self.lines.drain(start.line + 1..=end.line);
+            self.rows.drain(start.line + 1..=end.line);
+        }
+        self.rows[start.line] = None;
+        removed
+    }
+
+    /// inserts text at the position and returns the position after it
+    fn util_insert(&mut self, at: TextPosition, text: &str) -> TextPosition {
+        let mut parts = text.split('\n');
+        let first: Vec<char> = parts.next().unwrap_or("").chars().collect();
+        let rest: Vec<Vec<char>> = parts.map(|part| part.chars().collect()).collect();
+        let line: &mut Vec<char> = &mut self.lines[at.line];
+        self.rows[at.line] = None;
+        if rest.is_empty() {
+            let end: usize = at.column + first.len();
+            line.splice(at.column..at.column, first);
+            return TextPosition::new(at.line, end);
+        }
+        let tail: Vec<char> = line.split_off(at.column);
+        line.extend(first);
+        let count: usize = rest.len();
+        let end: TextPosition = TextPosition::new(at.line + count, rest[count - 1].len());
+        let mut rest: Vec<Vec<char>> = rest;
+        rest[count - 1].extend(tail);
+        self.lines.splice(at.line + 1..at.line + 1, rest);
+        self.rows
+            .splice(at.line + 1..at.line + 1, std::iter::repeat_n(None, count));
+        end
+    }
+}
+
+/// gets the position after text inserted at start
+fn util_end_of(start: TextPosition, text: &str) -> TextPosition {
+    match text.rfind('\n') {
+        Some(index) => TextPosition::new(
+            start.line + text.matches('\n').count(),
+            text[index + 1..].chars().count(),
+        ),
+        None => TextPosition::new(start.line, start.column + text.chars().count()),
+    }
+}
+
+/// gets the start columns of the rows of a line wrapped at width, rows break after the last
+/// space that fits or at width when a word is longer than a row
+fn util_wrap(line: &[char], width: usize) -> Vec<usize> {
+    let mut rows: Vec<usize> = vec![0];
+    let mut start: usize = 0;
+    while line.len() - start > width {
+        let limit: usize = start + width;
+        let end: usize = match line[start..limit].iter().rposition(|&c| c == ' ') {
+            Some(space) if space > 0 => start + space + 1,
+            _ => limit,
+        };
+        rows.push(end);
+        start = end;
+    }
+    rows
+}
+
+#[cfg(test)]
+mod multi_line_editing {
+    use crate::test_util::{self, row};
+    use crate::*;
+
+    fn key(area: &mut TextArea, code: u16, modifiers: u32) {
+        area.handle_event(&test_util::key(code, modifiers));
+    }
+
+    fn type_text(area: &mut TextArea, text: &str) {
+        for character in text.chars() {
+            if character == '\n' {
+                key(area, VK_RETURN, 0);
+            } else {
+                area.handle_event(&InputEvent::Key(KeyEvent::from_char(character)));
+            }
+        }
+    }
+
+    #[test]
+    fn edits_selects_and_undoes() {
+        let mut area: TextArea = TextArea::new((0, 0, 20, 5));
+        type_text(&mut area, "one two\nthree");
+        key(&mut area, VK_HOME, 0);
+        key(&mut area, VK_RIGHT, SHIFT_PRESSED);
+        key(&mut area, VK_RIGHT, SHIFT_PRESSED);
+        assert_eq!(area.selected_text(), "th");
+        key(&mut area, VK_X, LEFT_CTRL_PRESSED);
+        key(&mut area, VK_UP, 0);
+        key(&mut area, VK_V, LEFT_CTRL_PRESSED);
+        assert_eq!(area.text(), "thone two\nree");
+
+        key(&mut area, VK_END, 0);
+        key(&mut area, VK_DELETE, 0);
+        assert_eq!(area.text(), "thone tworee");
+        key(&mut area, VK_Z, LEFT_CTRL_PRESSED);
+        key(&mut area, VK_Z, LEFT_CTRL_PRESSED);
+        key(&mut area, VK_Z, LEFT_CTRL_PRESSED);
+        assert_eq!(area.text(), "one two\nthree");
+        key(&mut area, VK_Y, LEFT_CTRL_PRESSED);
+        assert_eq!(area.text(), "one two\nree");
+        assert_eq!(area.cursor(), TextPosition::new(1, 0));
+    }
+
+    #[test]
+    fn wraps_words_and_numbers_lines() {
+        let mut rusted = Rusted::new();
+        let mut area: TextArea = TextArea::new((0, 0, 10, 4)).line_numbers(true);
+        area.set_text("the quick brown fox\nend");
+        area.draw(&mut rusted);
+
+        assert_eq!(row(&rusted, 0, 10), "1 the     ");
+        assert_eq!(row(&rusted, 1, 10), "  quick   ");
+        assert_eq!(row(&rusted, 2, 10), "  brown   ");
+        assert_eq!(row(&rusted, 3, 10), "  fox     ");
+
+        key(&mut area, VK_END, LEFT_CTRL_PRESSED);
+        area.draw(&mut rusted);
+        assert_eq!(row(&rusted, 3, 10), "2 end     ");
+        assert_eq!(rusted.console.cursor, Coord(5, 3));
+        key(&mut area, VK_UP, 0);
+        assert_eq!(area.cursor(), TextPosition::new(0, 19));
+    }
+
+    #[test]
+    fn finds_and_replaces() {
+        let mut area: TextArea = TextArea::new((0, 0, 20, 5));
+        area.set_text("a cat\nthe cat sat");
+
+        assert!(area.find("cat"));
+        assert_eq!(area.selection().unwrap().0, TextPosition::new(0, 2));
+        assert!(area.replace("cat", "dog"));
+        assert_eq!(area.selection().unwrap().0, TextPosition::new(1, 4));
+        assert_eq!(area.replace_all("at", "og"), 2);
+        assert_eq!(area.text(), "a dog\nthe cog sog");
+        assert!(!area.find("cat"));
+
+        area.set_text("a,b\nc,d");
+        assert_eq!(area.replace_all(",", "\n"), 2);
+        assert_eq!(area.text(), "a\nb\nc\nd");
+        assert!(area.undo());
+        assert_eq!(area.text(), "a,b\nc,d");
+        assert!(!area.can_undo());
+    }
+
+    #[test]
+    fn scrolls_long_documents() {
+        let mut area: TextArea = TextArea::new((0, 0, 20, 5));
+        let text: String = (0..100_000)
+            .map(|line| line.to_string())
+            .collect::<Vec<String>>()
+            .join("\n");
+        area.set_text(&text);
+
+        key(&mut area, VK_END, LEFT_CTRL_PRESSED);
+        key(&mut area, VK_PRIOR, 0);
+
+        assert_eq!(area.cursor(), TextPosition::new(99_995, 5));
+        assert_eq!(area.line_count(), 100_000);
+    }
+
+    #[test]
+    fn scrolls_to_the_cursor_after_edits() {
+        let mut rusted = Rusted::new();
+        let mut area: TextArea = TextArea::new((0, 0, 10, 3));
+        let text: String = (0..10)
+            .map(|line| line.to_string())
+            .collect::<Vec<String>>()
+            .join("\n");
+        area.set_text(&text);
+        key(&mut area, VK_END, LEFT_CTRL_PRESSED);
+        area.draw(&mut rusted);
+        assert_eq!(row(&rusted, 2, 10), "9         ");
+
+        area.select(TextPosition::new(1, 0), TextPosition::new(9, 1));
+        area.cut();
+        area.draw(&mut rusted);
+        assert_eq!(area.text(), "0\n");
+        assert_eq!(row(&rusted, 0, 10), "          ");
+
+        area.undo();
+        area.draw(&mut rusted);
+        assert_eq!(row(&rusted, 2, 10), "9         ");
+
+        area.set_cursor(TextPosition::default());
+        assert!(area.find("8"));
+        area.draw(&mut rusted);
+        assert_eq!(row(&rusted, 2, 10), "8         ");
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::VecDeque;
+
+use crate::console::set_console_cursor_position;
+use crate::constants::*;
+use crate::geometry::Coord;
+use crate::input::{InputEvent, KeyEvent};
+use crate::rusted::Rusted;
+use crate::surface::Surface;
+
+/// what a [TextInput] did with an event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextInputEvent {
+    /// the text or the cursor changed and the field should be drawn again
+    Changed,
+    /// Enter was pressed, the text is added to the history
+    Submitted(String),
+    /// Escape was pressed
+    Cancelled,
+}
+
+/// a single-line text field with insert and overwrite modes, word-wise cursor movement,
+/// horizontal scrolling, password masking, input filtering and an optional history
+#[derive(Debug, Clone)]
+pub struct TextInput {
+    /// x, y of the field relative to the drawing origin
+    pub position: (i32, i32),
+    /// the number of columns shown, 0 is treated as 1
+    pub width: usize,
+    /// draws the field with the focused colors of the theme
+    pub focused: bool,
+    #[doc(hidden)]
+    text: Vec<char>,
+    #[doc(hidden)]
+    cursor: usize,
+    #[doc(hidden)]
+    scroll: usize,
+    #[doc(hidden)]
+    overwrite: bool,
+    #[doc(hidden)]
+    mask: Option<char>,
+    #[doc(hidden)]
+    max_length: Option<usize>,
+    #[doc(hidden)]
+    filter: Option<fn(char) -> bool>,
+    #[doc(hidden)]
+    history: VecDeque<String>,
+    #[doc(hidden)]
+    history_capacity: usize,
+    /// the history entry shown, None while editing new text
+    #[doc(hidden)]
+    history_index: Option<usize>,
+    /// the text that was being edited before browsing the history
+    #[doc(hidden)]
+    draft: Vec<char>,
+}
+
+impl TextInput {
+    pub fn new(x: i32, y: i32, width: usize) -> Self {
+        Self {
+            position: (x, y),
+            width: width.max(1),
+            focused: true,
+            text: vec![],
+            cursor: 0,
+            scroll: 0,
+            overwrite: false,
+            mask: None,
+            max_length: None,
+            filter: None,
+            history: VecDeque::new(),
+            history_capacity: 0,
+            history_index: None,
+            draft: vec![],
+        }
+    }
+
+    /// shows every character as mask
+    pub fn password(mut self, mask: char) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// only characters the filter accepts can be typed
+    pub fn filter(mut self, filter: fn(char) -> bool) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn digits_only(self) -> Self {
+        self.filter(|character| character.is_ascii_digit())
+    }
+
+    /// keeps up to capacity submitted texts, browsed with Up and Down
+    pub fn history(mut self, capacity: usize) -> Self {
+        self.history_capacity = capacity;
+        self
+    }
+
+    pub fn text(&self) -> String {
+        self.text.iter().collect()
+    }
+
+    /// replaces the text, cut to the maximum length, and moves the cursor to its end
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text
+            .chars()
+            .take(self.max_length.unwrap_or(usize::MAX))
+            .collect();
+        self.cursor = self.text.len();
+        self.util_scroll_to_cursor();
+    }
+
+    /// the index of the character in front of which the cursor is
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn is_overwrite(&self) -> bool {
+        self.overwrite
+    }
+
+    pub fn history_entries(&self) -> impl Iterator<Item = &String> {
+        self.history.iter()
+    }
+
+    pub fn handle_event(&mut self, event: &InputEvent) -> Option<TextInputEvent> {
+        match event {
+            InputEvent::Key(key) if key.key_down => self.handle_key(key),
+            _ => None,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> Option<TextInputEvent> {
+        let length: usize = self.text.len();
+        match key.virtual_key_code {
+            VK_LEFT if key.ctrl() => self.cursor = self.util_word_start(),
+            VK_RIGHT if key.ctrl() => self.cursor = self.util_word_end(),
+            VK_LEFT => self.cursor = self.cursor.saturating_sub(1),
+            VK_RIGHT => self.cursor = (self.cursor + 1).min(length),
+            VK_HOME => self.cursor = 0,
+            VK_END => self.cursor = length,
+            VK_INSERT => self.overwrite = !self.overwrite,
+            VK_BACK if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.text.remove(self.cursor);
+            }
+            VK_DELETE if self.cursor < length => {
+                self.text.remove(self.cursor);
+            }
+            VK_UP => self.util_browse_history(true),
+            VK_DOWN => self.util_browse_history(false),
+            VK_RETURN => {
+                let text: String = self.text();
+                self.util_remember(&text);
+                return Some(TextInputEvent::Submitted(text));
+            }
+            VK_ESCAPE => return Some(TextInputEvent::Cancelled),
+            _ if key.character >= ' ' && !key.ctrl() && !key.alt() => {
+                if !self.util_type(key.character) {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+        self.util_scroll_to_cursor();
+        Some(TextInputEvent::Changed)
+    }
+
+    /// draws the visible part of the field with the colors of the theme and places the cursor
+    pub fn draw(&self, ctx: &mut Rusted) {
+        ctx.push_state();
+        self.util_set_colors(ctx);
+        let (x, y) = self.position;
+        ctx.outchars(x, y, &self.util_visible());
+        let cursor: Coord = ctx.console.cursor;
+        ctx.pop_state();
+        set_console_cursor_position(&mut ctx.console, cursor);
+        if self.focused {
+            ctx.set_xy(x + (self.cursor - self.scroll) as i32, y);
+        }
+    }
+
+    /// draws the visible part of the field on any surface, the cursor is not shown
+    pub fn draw_on<S: Surface + ?Sized>(&self, ctx: &mut Rusted, surface: &mut S) {
+        ctx.push_state();
+        self.util_set_colors(ctx);
+        let (x, y) = self.position;
+        ctx.outchars_on(surface, x, y, &self.util_visible());
+        ctx.pop_state();
+    }
+
+    fn util_set_colors(&self, ctx: &mut Rusted) {
+        let (fgc, bgc) = if self.focused {
+            ctx.theme().focused
+        } else {
+            ctx.theme().input
+        };
+        ctx.set_fgcolor(fgc);
+        ctx.set_bgcolor(bgc);
+    }
+
+    /// gets the width columns of text from the scroll offset, masked and padded with spaces
+    fn util_visible(&self) -> String {
+        let mut visible: String = self
+            .text
+            .iter()
+            .skip(self.scroll)
+            .take(self.width.max(1))
+            .map(|&character| self.mask.unwrap_or(character))
+            .collect();
+        let shown: usize = visible.chars().count();
+        visible.push_str(&" ".repeat(self.width.max(1) - shown));
+        visible
+    }
+
+    /// inserts or overwrites at the cursor, false when the character is rejected
+    fn util_type(&mut self, character: char) -> bool {
+        if let Some(filter) = self.filter {
+            if !filter(character) {
+                return false;
+            }
+        }
+        if self.overwrite && self.cursor < self.text.len() {
+            self.text[self.cursor] = character;
+        } else {
+            if self.text.len() >= self.max_length.unwrap_or(usize::MAX) {
+                return false;
+            }
+            self.text.insert(self.cursor, character);
+        }
+        self.cursor += 1;
+        true
+    }
+
+    fn util_scroll_to_cursor(&mut self) {
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + self.width.max(1) {
+            self.scroll = self.cursor + 1 - self.width.max(1);
+        }
+    }
+
+    /// gets the start of the word before the cursor
+    fn util_word_start(&self) -> usize {
+        let mut index: usize = self.cursor;
+        while index > 0 && !self.text[index - 1].is_alphanumeric() {
+            index -= 1;
+        }
+        while index > 0 && self.text[index - 1].is_alphanumeric() {
+            index -= 1;
+        }
+        index
+    }
+
+    /// gets the start of the word after the cursor
+    fn util_word_end(&self) -> usize {
+        let mut index: usize = self.cursor;
+        while index < self.text.len() && self.text[index].is_alphanumeric() {
+            index += 1;
+        }
+        while index < self.text.len() && !self.text[index].is_alphanumeric() {
+            index += 1;
+        }
+        index
+    }
+
+    fn util_remember(&mut self, text: &str) {
+        self.history_index = None;
+        if self.history_capacity == 0 || text.is_empty() {
+            return;
+        }
+        if self.history.back().map(String::as_str) != Some(text) {
+            self.history.push_back(text.to_string());
+        }
+        while self.history.len() > self.history_capacity {
+            self.history.pop_front();
+        }
+    }
+
+    /// shows the previous or next history entry, going past the newest one shows the draft
+    fn util_browse_history(&mut self, older: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+        let newest: usize = self.history.len() - 1;
+        let index: Option<usize> = match (self.history_index, older) {
+            (None, true) => {
+                self.draft = self.text.clone();
+                Some(newest)
+            }
+            (None, false) => return,
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) if index < newest => Some(index + 1),
+            (Some(_), false) => None,
+        };
+        self.history_index = index;
+        self.text = match index {
+            Some(index) => self.history[index].chars().collect(),
+            None => std::mem::take(&mut self.draft),
+        };
+        self.cursor = self.text.len();
+    }
+}
+
+#[cfg(test)]
+mod text_editing {
+    use crate::*;
+
+    fn keys(input: &mut TextInput, text: &str) {
+        for character in text.chars() {
+            input.handle_event(&InputEvent::Key(KeyEvent::from_char(character)));
+        }
+    }
+
+    fn key(input: &mut TextInput, code: u16, modifiers: u32) -> Option<TextInputEvent> {
+        input.handle_event(&InputEvent::Key(
+            KeyEvent::new(code, '\0').with_modifiers(modifiers),
+        ))
+    }
+
+    #[test]
+    fn edits_with_cursor_keys_and_word_jumps() {
+        let mut input: TextInput = TextInput::new(0, 0, 20);
+        keys(&mut input, "hello world");
+        key(&mut input, VK_LEFT, LEFT_CTRL_PRESSED);
+        assert_eq!(input.cursor(), 6);
+        keys(&mut input, "big ");
+        key(&mut input, VK_HOME, 0);
+        key(&mut input, VK_INSERT, 0);
+        keys(&mut input, "J");
+        key(&mut input, VK_RIGHT, LEFT_CTRL_PRESSED);
+        key(&mut input, VK_BACK, 0);
+        key(&mut input, VK_END, 0);
+        key(&mut input, VK_DELETE, 0);
+
+        assert_eq!(input.text(), "Jellobig world");
+        assert_eq!(
+            key(&mut input, VK_RETURN, 0),
+            Some(TextInputEvent::Submitted("Jellobig world".to_string()))
+        );
+    }
+
+    #[test]
+    fn scrolls_masks_and_filters() {
+        let mut rusted = Rusted::new();
+        let mut input: TextInput = TextInput::new(2, 1, 4)
+            .password('*')
+            .digits_only()
+            .max_length(6);
+        keys(&mut input, "12a34567");
+        assert_eq!(input.text(), "123456");
+
+        input.draw(&mut rusted);
+        let shown: String = (2..6)
+            .map(|x| rusted.console.get_cell(Coord(x, 1)).unwrap().0)
+            .collect();
+        assert_eq!(shown, "*** ");
+        assert_eq!(rusted.console.cursor, Coord(5, 1));
+
+        key(&mut input, VK_HOME, 0);
+        input.draw(&mut rusted);
+        assert_eq!(rusted.console.cursor, Coord(2, 1));
+        assert_eq!(rusted.console.get_cell(Coord(5, 1)).unwrap().0, '*');
+    }
+
+    #[test]
+    fn treats_a_zero_width_as_one_column() {
+        let mut rusted = Rusted::new();
+        let mut input: TextInput = TextInput::new(0, 0, 4);
+        input.width = 0;
+        keys(&mut input, "abc");
+
+        input.draw(&mut rusted);
+        assert_eq!(rusted.console.get_cell(Coord(0, 0)).unwrap().0, ' ');
+        assert_eq!(rusted.console.get_cell(Coord(1, 0)).unwrap().0, '\0');
+        assert_eq!(rusted.console.cursor, Coord(0, 0));
+    }
+
+    #[test]
+    fn browses_the_history() {
+        let mut input: TextInput = TextInput::new(0, 0, 10).history(2);
+        for text in ["one", "two", "three"] {
+            input.set_text(text);
+            key(&mut input, VK_RETURN, 0);
+        }
+        input.set_text("draft");
+
+        key(&mut input, VK_UP, 0);
+        assert_eq!(input.text(), "three");
+        key(&mut input, VK_UP, 0);
+        key(&mut input, VK_UP, 0);
+        assert_eq!(input.text(), "two");
+        key(&mut input, VK_DOWN, 0);
+        key(&mut input, VK_DOWN, 0);
+        assert_eq!(input.text(), "draft");
+        assert_eq!(input.history_entries().count(), 2);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::frame_style::FrameStyle;
+use crate::window_options::WindowOptions;
+
+/// the colors and frame used by the widgets, colors are foreground, background pairs of the
+/// color indices taken by [crate::Rusted::set_fgcolor] and [crate::Rusted::set_bgcolor]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// windows opened with [Theme::window_options]
+    pub window: (u16, u16),
+    /// [crate::RustedMessage] and [crate::RustedChoice]
+    pub dialog: (u16, u16),
+    /// the element that has the focus
+    pub focused: (u16, u16),
+    /// selected items and text
+    pub selected: (u16, u16),
+    pub disabled: (u16, u16),
+    /// text fields and lists
+    pub input: (u16, u16),
+    pub menu: (u16, u16),
+    pub status: (u16, u16),
+    /// the foreground of hotkey letters
+    pub hotkey: u16,
+    /// the frame of windows and dialogs
+    pub frame: FrameStyle,
+    pub shadow: bool,
+}
+
+impl Theme {
+    /// describes a window with the window colors, frame and shadow of the theme
+    pub fn window_options(&self, rect: (i32, i32, i32, i32)) -> WindowOptions {
+        WindowOptions::new(rect)
+            .colors(self.window.0, self.window.1)
+            .frame(self.frame)
+            .shadow(self.shadow)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            window: (15, 4),
+            dialog: (15, 4),
+            focused: (0, 6),
+            selected: (15, 2),
+            disabled: (8, 4),
+            input: (15, 0),
+            menu: (0, 7),
+            status: (0, 7),
+            hotkey: 1,
+            frame: FrameStyle::Double,
+            shadow: true,
+        }
+    }
+}
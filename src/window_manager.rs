@@ -0,0 +1,446 @@
+use crate::console::{resize_console_buffer, Console, ResizeMode};
+use crate::geometry::{Coord, Rect, Size};
+use crate::rusted::Rusted;
+use crate::surface::Surface;
+use crate::window_options::WindowOptions;
+
+/// handle to one of the windows owned by a [WindowManager]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WindowId(usize);
+
+/// a window drawn into its own off-screen console
+#[derive(Debug, Clone)]
+pub struct ManagedWindow {
+    /// the screen position of the top-left cell, may lie outside of the screen
+    pub position: (i32, i32),
+    pub surface: Console,
+    pub visible: bool,
+    /// darkens two columns to the right of and one row below the window when composited
+    pub shadow: bool,
+    /// the smallest size interactive resizing allows
+    pub min_size: Size,
+    /// the largest size interactive resizing allows, the screen limits it further
+    pub max_size: Size,
+}
+
+/// keeps windows as off-screen surfaces in a z-ordered stack above a desktop surface and
+/// composites them onto a console. windows can be closed, raised or hidden in any order
+#[derive(Debug, Clone)]
+pub struct WindowManager {
+    #[doc(hidden)]
+    desktop: Console,
+    #[doc(hidden)]
+    windows: Vec<Option<ManagedWindow>>,
+    /// bottom to top
+    #[doc(hidden)]
+    z_order: Vec<WindowId>,
+    /// screen regions that must be composited again
+    #[doc(hidden)]
+    damage: Vec<Rect>,
+}
+
+impl WindowManager {
+    /// the desktop and the screen are columns * rows cells
+    pub fn new(columns: u16, rows: u16) -> Self {
+        Self {
+            desktop: Console::new(columns, rows),
+            windows: vec![],
+            z_order: vec![],
+            damage: vec![Rect(0, 0, columns, rows)],
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        Surface::size(&self.desktop)
+    }
+
+    /// the content underneath all windows
+    pub fn desktop(&self) -> &Console {
+        &self.desktop
+    }
+
+    /// changes to the desktop are picked up by the next [WindowManager::compose]
+    pub fn desktop_mut(&mut self) -> &mut Console {
+        &mut self.desktop
+    }
+
+    /// creates a visible, empty w * h window at x, y on top of all other windows
+    pub fn create(&mut self, position: (i32, i32), size: Size) -> WindowId {
+        let id: WindowId = WindowId(self.windows.len());
+        self.windows.push(Some(ManagedWindow {
+            position,
+            surface: Console::new(size.0, size.1),
+            visible: true,
+            shadow: false,
+            min_size: Size(1, 1),
+            max_size: Size(u16::MAX, u16::MAX),
+        }));
+        self.z_order.push(id);
+        self.util_damage_window(id);
+        id
+    }
+
+    /// creates a window at the position of the options and draws it into its surface with the
+    /// colors of rusted. returns the window and its client area in window coordinates
+    pub fn open(&mut self, rusted: &mut Rusted, options: &WindowOptions) -> (WindowId, Rect) {
+        let (x, y, w, h) = options.rect;
+        let size: Size = Size(
+            w.clamp(0, u16::MAX as i32) as u16,
+            h.clamp(0, u16::MAX as i32) as u16,
+        );
+        let id: WindowId = self.create((x, y), size);
+        let local: WindowOptions = WindowOptions {
+            rect: (0, 0, w, h),
+            shadow: false,
+            ..options.clone()
+        };
+
+        rusted.push_state();
+        let (ox, oy) = rusted.origin();
+        rusted.translate(-ox, -oy);
+        rusted.reset_clip();
+        if let Some((fgc, bgc)) = options.colors {
+            rusted.set_fgcolor(fgc);
+            rusted.set_bgcolor(bgc);
+        }
+        let window: &mut ManagedWindow = self.windows[id.0].as_mut().unwrap();
+        window.shadow = options.shadow;
+        rusted.cls_on(&mut window.surface);
+        let (_, client) = rusted.open_window_on(&mut window.surface, &local);
+        rusted.pop_state();
+        // create damaged the window before it knew about its shadow
+        self.util_damage_window(id);
+        (id, client)
+    }
+
+    /// removes the window, false when it does not exist
+    pub fn close(&mut self, id: WindowId) -> bool {
+        if self.window(id).is_none() {
+            return false;
+        }
+        self.util_damage_window(id);
+        self.windows[id.0] = None;
+        self.z_order.retain(|&other| other != id);
+        true
+    }
+
+    pub fn window(&self, id: WindowId) -> Option<&ManagedWindow> {
+        self.windows.get(id.0).and_then(|window| window.as_ref())
+    }
+
+    /// changes to the surface are picked up by the next [WindowManager::compose], changes to the
+    /// position, size or visibility must be made with the methods of the manager
+    pub fn surface_mut(&mut self, id: WindowId) -> Option<&mut Console> {
+        self.windows
+            .get_mut(id.0)
+            .and_then(|window| window.as_mut())
+            .map(|window| &mut window.surface)
+    }
+
+    /// the windows from bottom to top
+    pub fn z_order(&self) -> &[WindowId] {
+        &self.z_order
+    }
+
+    /// gets the topmost window
+    pub fn top(&self) -> Option<WindowId> {
+        self.z_order.last().copied()
+    }
+
+    /// moves the window on top of all other windows
+    pub fn raise(&mut self, id: WindowId) -> bool {
+        self.util_restack(id, true)
+    }
+
+    /// moves the window below all other windows
+    pub fn lower(&mut self, id: WindowId) -> bool {
+        self.util_restack(id, false)
+    }
+
+    pub fn set_visible(&mut self, id: WindowId, visible: bool) -> bool {
+        match self
+            .windows
+            .get_mut(id.0)
+            .and_then(|window| window.as_mut())
+        {
+            Some(window) if window.visible != visible => {
+                window.visible = visible;
+                self.util_damage_window(id);
+                true
+            }
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    pub fn move_to(&mut self, id: WindowId, x: i32, y: i32) -> bool {
+        if self.window(id).is_none() {
+            return false;
+        }
+        self.util_damage_window(id);
+        self.windows[id.0].as_mut().unwrap().position = (x, y);
+        self.util_damage_window(id);
+        true
+    }
+
+    /// resizes the surface of the window, keeping its top-left contents
+    pub fn resize(&mut self, id: WindowId, size: Size) -> bool {
+        if self.window(id).is_none() {
+            return false;
+        }
+        self.util_damage_window(id);
+        let window: &mut ManagedWindow = self.windows[id.0].as_mut().unwrap();
+        resize_console_buffer(&mut window.surface, Coord::from(size), ResizeMode::Crop);
+        self.util_damage_window(id);
+        true
+    }
+
+    /// sets the sizes interactive resizing is limited to
+    pub fn set_size_limits(&mut self, id: WindowId, min_size: Size, max_size: Size) -> bool {
+        match self
+            .windows
+            .get_mut(id.0)
+            .and_then(|window| window.as_mut())
+        {
+            Some(window) => {
+                window.min_size = min_size;
+                window.max_size = max_size;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// gets x, y, w, h of the window in screen coordinates, without its shadow
+    pub fn window_rect(&self, id: WindowId) -> Option<(i32, i32, i32, i32)> {
+        self.window(id).map(|window| {
+            let Size(w, h) = Surface::size(&window.surface);
+            (window.position.0, window.position.1, w as i32, h as i32)
+        })
+    }
+
+    /// gets the topmost visible window covering the screen coord
+    pub fn window_at(&self, coord: Coord) -> Option<WindowId> {
+        let (x, y) = (coord.0 as i32, coord.1 as i32);
+        self.z_order.iter().rev().copied().find(|&id| {
+            let visible: bool = self.window(id).map(|window| window.visible) == Some(true);
+            let (wx, wy, ww, wh) = self.window_rect(id).unwrap();
+            visible && x >= wx && x < wx + ww && y >= wy && y < wy + wh
+        })
+    }
+
+    /// marks the whole screen to be composited again, such as after the target console changed
+    pub fn invalidate(&mut self) {
+        self.damage = vec![Rect::from_position_size(Coord(0, 0), self.size())];
+    }
+
+    /// draws the desktop and the visible windows from bottom to top onto target, limited to the
+    /// regions that changed since the last call
+    pub fn compose(&mut self, target: &mut Console) {
+        let mut damage: Vec<Rect> = std::mem::take(&mut self.damage);
+        damage.extend(self.desktop.take_dirty_rects());
+        for index in 0..self.windows.len() {
+            let Some(window) = self.windows[index].as_mut() else {
+                continue;
+            };
+            let (x, y) = window.position;
+            let changed: Vec<Rect> = window.surface.take_dirty_rects();
+            if window.visible {
+                damage.extend(changed.into_iter().filter_map(|rect| {
+                    util_screen_rect(x + rect.0 as i32, y + rect.1 as i32, rect.size())
+                }));
+            }
+        }
+
+        let screen: Rect = Rect::from_position_size(Coord(0, 0), self.size());
+        for region in damage {
+            let region: Rect = region.clip_to(&screen);
+            if region.is_empty() {
+                continue;
+            }
+            target.blit(&self.desktop, region, region.position());
+            for id in &self.z_order {
+                let window: &ManagedWindow = self.windows[id.0].as_ref().unwrap();
+                if !window.visible {
+                    continue;
+                }
+                let (x, y) = window.position;
+                let size: Size = Surface::size(&window.surface);
+                if let Some(covered) =
+                    util_screen_rect(x, y, size).and_then(|rect| rect.intersection(&region))
+                {
+                    let src: Rect = Rect::from_position_size(
+                        Coord((covered.0 as i32 - x) as u16, (covered.1 as i32 - y) as u16),
+                        covered.size(),
+                    );
+                    target.blit(&window.surface, src, covered.position());
+                }
+                if window.shadow {
+                    for (sx, sy, sw, sh) in util_shadow_rects(x, y, size) {
+                        let shadow: Option<Rect> = util_screen_rect(sx, sy, Size(sw, sh))
+                            .and_then(|rect| rect.intersection(&region));
+                        if let Some(shadow) = shadow {
+                            target.darken_rect(shadow);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn util_restack(&mut self, id: WindowId, to_top: bool) -> bool {
+        let Some(index) = self.z_order.iter().position(|&other| other == id) else {
+            return false;
+        };
+        self.z_order.remove(index);
+        if to_top {
+            self.z_order.push(id);
+        } else {
+            self.z_order.insert(0, id);
+        }
+        self.util_damage_window(id);
+        true
+    }
+
+    /// marks the screen cells covered by the window and its shadow to be composited again
+    fn util_damage_window(&mut self, id: WindowId) {
+        let Some(window) = self.window(id) else {
+            return;
+        };
+        let (x, y) = window.position;
+        let Size(w, h) = Surface::size(&window.surface);
+        let extra: (u16, u16) = if window.shadow { (2, 1) } else { (0, 0) };
+        let size: Size = Size(w.saturating_add(extra.0), h.saturating_add(extra.1));
+        if let Some(rect) = util_screen_rect(x, y, size) {
+            self.damage.push(rect);
+        }
+    }
+}
+
+/// gets the part of the size region at x, y that has non-negative screen coordinates
+fn util_screen_rect(x: i32, y: i32, size: Size) -> Option<Rect> {
+    let clamp = |value: i32| value.clamp(0, u16::MAX as i32) as u16;
+    let rect: Rect = Rect(
+        clamp(x),
+        clamp(y),
+        clamp(x + size.0 as i32),
+        clamp(y + size.1 as i32),
+    );
+    (!rect.is_empty()).then_some(rect)
+}
+
+/// gets x, y, w, h of the right and bottom parts of the shadow of a window
+fn util_shadow_rects(x: i32, y: i32, size: Size) -> [(i32, i32, u16, u16); 2] {
+    let Size(w, h) = size;
+    [
+        (x + w as i32, y + 1, 2, h),
+        (x + 2, y + h as i32, w.saturating_sub(2), 1),
+    ]
+}
+
+#[cfg(test)]
+mod z_order {
+    use crate::*;
+
+    fn row(console: &Console, y: u16) -> String {
+        (0..console.size.0)
+            .map(|x| console.get_cell(Coord(x, y)).unwrap().0)
+            .collect()
+    }
+
+    #[test]
+    fn closes_and_raises_windows_in_any_order() {
+        let mut screen: Console = Console::new(8, 1);
+        let mut manager: WindowManager = WindowManager::new(8, 1);
+        manager
+            .desktop_mut()
+            .fill_rect(Rect(0, 0, 8, 1), CharInfo('.', Attribute(7)));
+        let lower: WindowId = manager.create((1, 0), Size(4, 1));
+        let upper: WindowId = manager.create((3, 0), Size(4, 1));
+        manager
+            .surface_mut(lower)
+            .unwrap()
+            .fill_rect(Rect(0, 0, 4, 1), CharInfo('a', Attribute(7)));
+        manager
+            .surface_mut(upper)
+            .unwrap()
+            .fill_rect(Rect(0, 0, 4, 1), CharInfo('b', Attribute(7)));
+
+        manager.compose(&mut screen);
+        assert_eq!(row(&screen, 0), ".aabbbb.");
+
+        manager.raise(lower);
+        manager.compose(&mut screen);
+        assert_eq!(row(&screen, 0), ".aaaabb.");
+
+        manager.close(lower);
+        manager.compose(&mut screen);
+        assert_eq!(row(&screen, 0), "...bbbb.");
+
+        manager.set_visible(upper, false);
+        manager.move_to(upper, -2, 0);
+        manager.compose(&mut screen);
+        assert_eq!(row(&screen, 0), "........");
+
+        manager.set_visible(upper, true);
+        manager.compose(&mut screen);
+        assert_eq!(row(&screen, 0), "bb......");
+        assert_eq!(manager.window_at(Coord(1, 0)), Some(upper));
+        assert_eq!(manager.window_at(Coord(2, 0)), None);
+    }
+
+    #[test]
+    fn composites_only_changed_regions() {
+        let mut rusted: Rusted = Rusted::new();
+        let mut manager: WindowManager = WindowManager::new(80, 25);
+        let (id, client) = manager.open(
+            &mut rusted,
+            &WindowOptions::new((10, 5, 20, 6))
+                .colors(15, 1)
+                .shadow(true),
+        );
+        assert_eq!(client, Rect(1, 1, 19, 5));
+        manager.compose(&mut rusted.console);
+        rusted.console.take_dirty_rects();
+
+        rusted.outchars_on(manager.surface_mut(id).unwrap(), 2, 2, "hi");
+        manager.compose(&mut rusted.console);
+
+        assert_eq!(rusted.console.take_dirty_rects(), vec![Rect(12, 7, 14, 8)]);
+        assert_eq!(rusted.console.get_cell(Coord(12, 7)).unwrap().0, 'h');
+        assert_eq!(
+            rusted.console.get_cell(Coord(10, 5)).unwrap().0,
+            BOX_TOPLEFT_DOUBLE
+        );
+        assert_eq!(
+            rusted.console.get_cell(Coord(31, 10)).unwrap().1,
+            Attribute(0)
+        );
+    }
+
+    #[test]
+    fn draws_the_shadow_of_windows_opened_after_a_compose() {
+        let mut rusted: Rusted = Rusted::new();
+        let mut manager: WindowManager = WindowManager::new(80, 25);
+        let under: Attribute = Attribute(FOREGROUND_INTENSITY | BACKGROUND_BLUE);
+        manager
+            .desktop_mut()
+            .fill_rect(Rect(0, 0, 80, 25), CharInfo('.', under));
+        manager.compose(&mut rusted.console);
+
+        manager.open(
+            &mut rusted,
+            &WindowOptions::new((10, 5, 20, 6))
+                .colors(15, 1)
+                .shadow(true),
+        );
+        manager.compose(&mut rusted.console);
+
+        for cell in [Coord(31, 10), Coord(15, 11)] {
+            assert_eq!(
+                rusted.console.get_cell(cell).unwrap(),
+                CharInfo('.', under.darkened())
+            );
+        }
+    }
+}
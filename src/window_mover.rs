@@ -0,0 +1,300 @@
+use crate::constants::*;
+use crate::geometry::Size;
+use crate::input::{InputEvent, KeyEvent, MouseEvent};
+use crate::window_manager::{WindowId, WindowManager};
+
+/// what an interactive move or resize did to a window. the content of a resized window should
+/// be laid out and drawn again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowChange {
+    Moved { id: WindowId, position: (i32, i32) },
+    Resized { id: WindowId, size: Size },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Drag {
+    None,
+    /// grab is the offset of the mouse from the top-left of the window
+    Move {
+        id: WindowId,
+        grab: (i32, i32),
+    },
+    Resize {
+        id: WindowId,
+    },
+    /// keeps the rect to go back to when the move is cancelled
+    Keyboard {
+        id: WindowId,
+        original: (i32, i32, i32, i32),
+    },
+}
+
+/// moves and resizes the windows of a [WindowManager] with the mouse and keyboard.
+/// dragging the top row of a window moves it, dragging its bottom-right cell resizes it.
+/// in keyboard mode the arrows, with or without Ctrl, move the window and Shift+arrows resize
+/// it, Enter accepts and Escape puts it back
+#[derive(Debug, Clone)]
+pub struct WindowMover {
+    #[doc(hidden)]
+    drag: Drag,
+    /// the mouse buttons held down after the last mouse event
+    #[doc(hidden)]
+    buttons: u32,
+}
+
+impl WindowMover {
+    pub fn new() -> Self {
+        Self {
+            drag: Drag::None,
+            buttons: 0,
+        }
+    }
+
+    /// true while a drag or keyboard move is in progress, events are then consumed by the mover
+    pub fn is_active(&self) -> bool {
+        self.drag != Drag::None
+    }
+
+    /// starts moving and resizing the window with the keyboard, and raises it
+    pub fn begin_keyboard(&mut self, manager: &mut WindowManager, id: WindowId) -> bool {
+        let Some(original) = manager.window_rect(id) else {
+            return false;
+        };
+        manager.raise(id);
+        self.drag = Drag::Keyboard { id, original };
+        true
+    }
+
+    pub fn handle_event(
+        &mut self,
+        manager: &mut WindowManager,
+        event: &InputEvent,
+    ) -> Option<WindowChange> {
+        match event {
+            InputEvent::Mouse(mouse) => {
+                let change: Option<WindowChange> = self.util_mouse(manager, mouse);
+                self.buttons = mouse.button_state;
+                change
+            }
+            InputEvent::Key(key) if key.key_down => self.util_key(manager, key),
+            _ => None,
+        }
+    }
+
+    fn util_mouse(
+        &mut self,
+        manager: &mut WindowManager,
+        mouse: &MouseEvent,
+    ) -> Option<WindowChange> {
+        let (x, y) = (mouse.position.0 as i32, mouse.position.1 as i32);
+        let pressed: bool = mouse.left_button() && self.buttons & FROM_LEFT_1ST_BUTTON_PRESSED == 0;
+        if !mouse.left_button() {
+            if matches!(self.drag, Drag::Move { .. } | Drag::Resize { .. }) {
+                self.drag = Drag::None;
+            }
+            return None;
+        }
+        if pressed {
+            let id: WindowId = manager.window_at(mouse.position)?;
+            manager.raise(id);
+            let (wx, wy, ww, wh) = manager.window_rect(id)?;
+            if (x, y) == (wx + ww - 1, wy + wh - 1) {
+                self.drag = Drag::Resize { id };
+            } else if y == wy {
+                self.drag = Drag::Move {
+                    id,
+                    grab: (x - wx, y - wy),
+                };
+            }
+            return None;
+        }
+        match self.drag {
+            Drag::Move { id, grab } => util_move(manager, id, (x - grab.0, y - grab.1)),
+            Drag::Resize { id } => {
+                let (wx, wy, _, _) = manager.window_rect(id)?;
+                util_resize(manager, id, (x - wx + 1, y - wy + 1))
+            }
+            _ => None,
+        }
+    }
+
+    fn util_key(&mut self, manager: &mut WindowManager, key: &KeyEvent) -> Option<WindowChange> {
+        let Drag::Keyboard { id, original } = self.drag else {
+            return None;
+        };
+        let (x, y, w, h) = manager.window_rect(id)?;
+        let (dx, dy) = match key.virtual_key_code {
+            VK_LEFT => (-1, 0),
+            VK_RIGHT => (1, 0),
+            VK_UP => (0, -1),
+            VK_DOWN => (0, 1),
+            VK_RETURN => {
+                self.drag = Drag::None;
+                return None;
+            }
+            VK_ESCAPE => {
+                self.drag = Drag::None;
+                let (ox, oy, ow, oh) = original;
+                // resize before moving, a grown window could not move back to where it was, and
+                // again after, a window that moved right could not grow back to its size
+                let resized: Option<WindowChange> = util_resize(manager, id, (ow, oh));
+                let moved: Option<WindowChange> = util_move(manager, id, (ox, oy));
+                return util_resize(manager, id, (ow, oh)).or(resized).or(moved);
+            }
+            _ => return None,
+        };
+        if key.shift() {
+            util_resize(manager, id, (w + dx, h + dy))
+        } else {
+            util_move(manager, id, (x + dx, y + dy))
+        }
+    }
+}
+
+impl Default for WindowMover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// moves the window as close to position as the screen allows
+fn util_move(
+    manager: &mut WindowManager,
+    id: WindowId,
+    position: (i32, i32),
+) -> Option<WindowChange> {
+    let (x, y, w, h) = manager.window_rect(id)?;
+    let Size(columns, rows) = manager.size();
+    let position: (i32, i32) = (
+        position.0.min(columns as i32 - w).max(0),
+        position.1.min(rows as i32 - h).max(0),
+    );
+    if position == (x, y) {
+        return None;
+    }
+    manager.move_to(id, position.0, position.1);
+    Some(WindowChange::Moved { id, position })
+}
+
+/// resizes the window as close to size as its limits and the screen allow
+fn util_resize(
+    manager: &mut WindowManager,
+    id: WindowId,
+    size: (i32, i32),
+) -> Option<WindowChange> {
+    let (x, y, w, h) = manager.window_rect(id)?;
+    let window = manager.window(id)?;
+    let (min, max) = (window.min_size, window.max_size);
+    let Size(columns, rows) = manager.size();
+    let limit = |value: i32, min: u16, max: u16, space: i32| {
+        value.min(max as i32).min(space).max(min as i32).max(1)
+    };
+    let size: Size = Size(
+        limit(size.0, min.0, max.0, columns as i32 - x) as u16,
+        limit(size.1, min.1, max.1, rows as i32 - y) as u16,
+    );
+    if (size.0 as i32, size.1 as i32) == (w, h) {
+        return None;
+    }
+    manager.resize(id, size);
+    Some(WindowChange::Resized { id, size })
+}
+
+#[cfg(test)]
+mod move_and_resize {
+    use crate::*;
+
+    fn mouse(x: u16, y: u16, buttons: u32, flags: u32) -> InputEvent {
+        InputEvent::Mouse(MouseEvent::new(Coord(x, y), buttons, flags))
+    }
+
+    #[test]
+    fn drags_title_bars_and_corners_with_the_mouse() {
+        let mut manager: WindowManager = WindowManager::new(20, 10);
+        let id: WindowId = manager.create((2, 2), Size(6, 4));
+        manager.set_size_limits(id, Size(4, 3), Size(10, 5));
+        let mut mover: WindowMover = WindowMover::new();
+        let left: u32 = FROM_LEFT_1ST_BUTTON_PRESSED;
+
+        assert_eq!(
+            mover.handle_event(&mut manager, &mouse(4, 2, left, 0)),
+            None
+        );
+        assert!(mover.is_active());
+        assert_eq!(
+            mover.handle_event(&mut manager, &mouse(7, 3, left, MOUSE_MOVED)),
+            Some(WindowChange::Moved {
+                id,
+                position: (5, 3)
+            })
+        );
+        // the window stays inside of the screen
+        mover.handle_event(&mut manager, &mouse(30, 30, left, MOUSE_MOVED));
+        assert_eq!(manager.window_rect(id), Some((14, 6, 6, 4)));
+        mover.handle_event(&mut manager, &mouse(30, 30, 0, MOUSE_MOVED));
+        assert!(!mover.is_active());
+
+        manager.move_to(id, 0, 0);
+        mover.handle_event(&mut manager, &mouse(5, 3, left, 0));
+        assert_eq!(
+            mover.handle_event(&mut manager, &mouse(15, 1, left, MOUSE_MOVED)),
+            Some(WindowChange::Resized {
+                id,
+                size: Size(10, 3)
+            })
+        );
+        assert_eq!(manager.window(id).unwrap().surface.size, Coord(10, 3));
+    }
+
+    #[test]
+    fn moves_and_resizes_with_the_keyboard() {
+        let mut manager: WindowManager = WindowManager::new(20, 10);
+        let id: WindowId = manager.create((2, 2), Size(6, 4));
+        let mut mover: WindowMover = WindowMover::new();
+        let key = |code: u16, modifiers: u32| {
+            InputEvent::Key(KeyEvent::new(code, '\0').with_modifiers(modifiers))
+        };
+
+        assert_eq!(mover.handle_event(&mut manager, &key(VK_LEFT, 0)), None);
+        mover.begin_keyboard(&mut manager, id);
+        mover.handle_event(&mut manager, &key(VK_LEFT, LEFT_CTRL_PRESSED));
+        mover.handle_event(&mut manager, &key(VK_DOWN, 0));
+        assert_eq!(
+            mover.handle_event(&mut manager, &key(VK_RIGHT, SHIFT_PRESSED)),
+            Some(WindowChange::Resized {
+                id,
+                size: Size(7, 4)
+            })
+        );
+        assert_eq!(manager.window_rect(id), Some((1, 3, 7, 4)));
+
+        mover.handle_event(&mut manager, &key(VK_ESCAPE, 0));
+        assert_eq!(manager.window_rect(id), Some((2, 2, 6, 4)));
+        assert!(!mover.is_active());
+
+        // grown past its original x, and moved right of it while wider than it became
+        let grown: WindowId = manager.create((10, 2), Size(4, 4));
+        mover.begin_keyboard(&mut manager, grown);
+        for _ in 0..10 {
+            mover.handle_event(&mut manager, &key(VK_LEFT, 0));
+        }
+        for _ in 0..16 {
+            mover.handle_event(&mut manager, &key(VK_RIGHT, SHIFT_PRESSED));
+        }
+        assert_eq!(manager.window_rect(grown), Some((0, 2, 20, 4)));
+        mover.handle_event(&mut manager, &key(VK_ESCAPE, 0));
+        assert_eq!(manager.window_rect(grown), Some((10, 2, 4, 4)));
+
+        let wide: WindowId = manager.create((0, 5), Size(18, 2));
+        mover.begin_keyboard(&mut manager, wide);
+        for _ in 0..10 {
+            mover.handle_event(&mut manager, &key(VK_LEFT, SHIFT_PRESSED));
+        }
+        for _ in 0..10 {
+            mover.handle_event(&mut manager, &key(VK_RIGHT, 0));
+        }
+        assert_eq!(manager.window_rect(wide), Some((10, 5, 8, 2)));
+        mover.handle_event(&mut manager, &key(VK_ESCAPE, 0));
+        assert_eq!(manager.window_rect(wide), Some((0, 5, 18, 2)));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::frame_style::FrameStyle;
+
+/// where a line of text is placed within the space available to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl TextAlign {
+    /// gets the offset of text that is length columns wide within width columns
+    pub fn offset(&self, length: i32, width: i32) -> i32 {
+        match self {
+            TextAlign::Left => 0,
+            TextAlign::Center => (width - length).max(0) / 2,
+            TextAlign::Right => (width - length).max(0),
+        }
+    }
+}
+
+/// describes how [crate::Rusted::open_window] draws a window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowOptions {
+    /// x, y, w, h of the window including its frame
+    pub rect: (i32, i32, i32, i32),
+    /// foreground and background color, the current colors when None
+    pub colors: Option<(u16, u16)>,
+    pub frame: FrameStyle,
+    pub shadow: bool,
+    pub title: Option<String>,
+    pub title_align: TextAlign,
+    pub footer: Option<String>,
+    pub footer_align: TextAlign,
+    /// drawn as `[glyph]` at the right end of the top edge
+    pub close_glyph: Option<char>,
+    /// the columns and rows left empty between the frame and the client area
+    pub padding: (i32, i32),
+}
+
+impl WindowOptions {
+    /// a double framed window without decorations
+    pub fn new(rect: (i32, i32, i32, i32)) -> Self {
+        Self {
+            rect,
+            colors: None,
+            frame: FrameStyle::default(),
+            shadow: false,
+            title: None,
+            title_align: TextAlign::Center,
+            footer: None,
+            footer_align: TextAlign::Right,
+            close_glyph: None,
+            padding: (0, 0),
+        }
+    }
+
+    pub fn colors(mut self, fgc: u16, bgc: u16) -> Self {
+        self.colors = Some((fgc, bgc));
+        self
+    }
+
+    pub fn frame(mut self, frame: impl Into<FrameStyle>) -> Self {
+        self.frame = frame.into();
+        self
+    }
+
+    /// darkens two columns to the right of and one row below the window
+    pub fn shadow(mut self, shadow: bool) -> Self {
+        self.shadow = shadow;
+        self
+    }
+
+    /// text drawn into the top edge
+    pub fn title(mut self, title: &str, align: TextAlign) -> Self {
+        self.title = Some(title.to_string());
+        self.title_align = align;
+        self
+    }
+
+    /// text drawn into the bottom edge
+    pub fn footer(mut self, footer: &str, align: TextAlign) -> Self {
+        self.footer = Some(footer.to_string());
+        self.footer_align = align;
+        self
+    }
+
+    pub fn close_glyph(mut self, glyph: char) -> Self {
+        self.close_glyph = Some(glyph);
+        self
+    }
+
+    pub fn padding(mut self, columns: i32, rows: i32) -> Self {
+        self.padding = (columns.max(0), rows.max(0));
+        self
+    }
+
+    /// gets x, y, w, h of the area inside the frame and padding
+    pub fn client_rect(&self) -> (i32, i32, i32, i32) {
+        let (x, y, w, h) = self.rect;
+        let border: i32 = match self.frame {
+            FrameStyle::None => 0,
+            _ => 1,
+        };
+        let (dx, dy) = (border + self.padding.0, border + self.padding.1);
+        (x + dx, y + dy, (w - dx * 2).max(0), (h - dy * 2).max(0))
+    }
+
+    /// gets x, y, w, h of the close marker, None without a close glyph
+    pub fn close_rect(&self) -> Option<(i32, i32, i32, i32)> {
+        let (x, y, w, _) = self.rect;
+        self.close_glyph.map(|_| (x + w - 5, y, 3, 1))
+    }
+}